@@ -0,0 +1,112 @@
+use quote::quote;
+
+use crate::layout::{TextDerive, derive_text};
+use crate::syntax::format::Format;
+use crate::syntax::placeholder::Placeholder;
+
+/// The attributes that configure the derived implementation of `defmt::Format`, in ascending order of precedence. Its
+/// output resembles that of `Debug`, so the attributes of `Debug` apply as well.
+pub const ATTRIBUTES: &[&str] = &["fmt", "debug", "defmt"];
+
+pub fn defmt(item: proc_macro::TokenStream, use_rt: &proc_macro2::TokenStream) -> proc_macro::TokenStream {
+	derive_text(item, use_rt, &Defmt)
+}
+
+/// Writes the output with `defmt::write!`, which interns its format strings.
+struct Defmt;
+
+/// The output of a single printed field: a `defmt` format string together with its arguments.
+struct Field {
+	format: String,
+	arguments: Vec<proc_macro2::TokenStream>,
+}
+
+impl TextDerive for Defmt {
+	type Field = Field;
+
+	fn name(&self) -> &'static str {
+		"defmt"
+	}
+
+	fn attributes(&self) -> &'static [&'static str] {
+		ATTRIBUTES
+	}
+
+	fn trait_name(&self) -> &'static str {
+		"Format"
+	}
+
+	fn trait_path(&self) -> proc_macro2::TokenStream {
+		quote!(defmt::Format)
+	}
+
+	fn signature(&self) -> proc_macro2::TokenStream {
+		quote!(fn format(&self, fmt_derive_formatter_variable: defmt::Formatter))
+	}
+
+	fn text(&self, text: &str) -> Field {
+		Field {
+			format: escape(text),
+			arguments: Vec::new(),
+		}
+	}
+
+	fn format(&self, format: &Format, scope: &syn::Fields) -> syn::Result<Field> {
+		let (format, arguments) = format.defmt(scope)?;
+		Ok(Field { format, arguments })
+	}
+
+	fn placeholder(&self, placeholder: &Placeholder, ty: &syn::Type, var_name: &syn::Ident) -> syn::Result<Field> {
+		let placeholder = placeholder.expand_defmt(ty)?;
+		Ok(Field {
+			format: "{}".to_owned(),
+			arguments: vec![quote!(_rt::DefmtOrReplacement::<#ty>(#var_name).defmt(|| #placeholder))],
+		})
+	}
+
+	fn write_field(&self, prefix: &str, field: &Field) -> proc_macro2::TokenStream {
+		let format = syn::LitStr::new(
+			&format!("{}{}", escape(prefix), field.format),
+			proc_macro2::Span::call_site(),
+		);
+		let arguments = &field.arguments;
+		quote!(defmt::write!(fmt_derive_formatter_variable, #format #(, #arguments)*);)
+	}
+
+	/// A field that is printed by `defmt::Format` is passed the formatter directly.
+	fn forward(&self, field: &Field) -> proc_macro2::TokenStream {
+		match field.arguments.as_slice() {
+			[argument] if field.format == "{}" => quote!(defmt::Format::format(&#argument, fmt_derive_formatter_variable);),
+			_ => self.write_field("", field),
+		}
+	}
+
+	fn write_str(&self, text: &str) -> proc_macro2::TokenStream {
+		match text {
+			"" => quote!(),
+			text => {
+				let text = escape(text);
+				quote!(defmt::write!(fmt_derive_formatter_variable, #text);)
+			}
+		}
+	}
+
+	/// All fields are written by a single format string, which is interned only once.
+	fn write_fields(&self, fields: &[(String, &Field)], close: &str) -> proc_macro2::TokenStream {
+		let mut format = String::new();
+		let mut arguments = Vec::new();
+		for (prefix, field) in fields {
+			format.push_str(&escape(prefix));
+			format.push_str(&field.format);
+			arguments.extend(field.arguments.iter());
+		}
+		format.push_str(&escape(close));
+		let format = syn::LitStr::new(&format, proc_macro2::Span::call_site());
+		quote!(defmt::write!(fmt_derive_formatter_variable, #format #(, #arguments)*);)
+	}
+}
+
+/// Escapes the braces in `text`, so that it is printed as is by `defmt::write!`.
+fn escape(text: &str) -> String {
+	text.replace('{', "{{").replace('}', "}}")
+}
@@ -0,0 +1,656 @@
+use proc_macro_error2::{abort_if_dirty, emit_error};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse_macro_input;
+
+use crate::fmt_trait::FmtTrait;
+use crate::generics::{Bounds, GenericVariants};
+use crate::layout::Layout;
+use crate::non_exhaustive::NonExhaustive;
+use crate::strict::Strict;
+use crate::syntax::placeholder::Placeholder;
+use crate::syntax::{KeyValueAttribute, field_attribute, item_attribute, parse_attributes, variant_attribute};
+use crate::{manifest, rename};
+
+pub fn derive(
+	item: proc_macro::TokenStream,
+	use_rt: &proc_macro2::TokenStream,
+	fmt_trait: &FmtTrait,
+) -> proc_macro::TokenStream {
+	let item = parse_macro_input!(item as syn::DeriveInput);
+	let item_name = &item.ident;
+
+	let item_config: item_attribute::ItemAttribute = parse_attributes(&item.attrs, fmt_trait.attributes);
+	if !matches!(item.data, syn::Data::Union(_)) {
+		for key in item_config.unsafe_keys() {
+			emit_error!(key, "`{}` can only be used on a `union`", key);
+		}
+	} else if let Some(key) = item_config.keys.iter().find(|key| *key == "transparent") {
+		emit_error!(
+			key, "`transparent` cannot be used on a `union`";
+			help = "use `unsafe(variant = ...)` to print its active field"
+		);
+	}
+	if item_config.layout == Some(Layout::Logfmt) && item_config.non_exhaustive == Some(NonExhaustive::Always) {
+		for key in item_config.keys.iter().filter(|key| *key == "non_exhaustive") {
+			emit_error!(
+				key,
+				"`non_exhaustive` cannot be used with `layout = \"logfmt\"`, which has no notation for omitted fields"
+			);
+		}
+	}
+	let field_defaults = field_defaults(&item_config);
+	abort_if_dirty();
+
+	let layout = item_config.layout.unwrap_or_default();
+	let non_exhaustive = item_config.non_exhaustive.unwrap_or_default();
+	let trait_name = fmt_trait.ident();
+	let mut bounds = Bounds::new(
+		&item.generics,
+		fmt_trait.path(),
+		item_config.bound.as_ref(),
+		item_config.infer_bounds,
+	);
+
+	let implementation = match item_config.format {
+		Some(format) => {
+			let fields = match &item.data {
+				syn::Data::Struct(item_struct) => item_struct.fields.clone(),
+				syn::Data::Enum(_) | syn::Data::Union(_) => syn::Fields::Unit,
+			};
+			let format = format.expand(&fields);
+			let mut result = match fields {
+				syn::Fields::Unit => quote!(),
+				syn::Fields::Unnamed(fields) => {
+					let mut destructure = quote!();
+					for (field_number, _field) in fields.unnamed.iter().enumerate() {
+						let var_name = proc_macro2::Ident::new(&format!("_{}", field_number), proc_macro2::Span::call_site());
+						destructure.extend(quote!(#var_name, ))
+					}
+					quote!(#[allow(unused_variables)] let #item_name(#destructure) = self;)
+				}
+				syn::Fields::Named(fields) => {
+					let mut destructure = quote!();
+					for field in fields.named {
+						let var_name = field.ident.expect("a named field should always have a name");
+						destructure.extend(quote!(#var_name, ))
+					}
+					quote!(#[allow(unused_variables)] let #item_name{#destructure} = self;)
+				}
+			};
+			result.extend(quote!(::core::write!(fmt_derive_formatter_variable, #format)));
+			result
+		}
+		None => match item.data {
+			syn::Data::Struct(item_struct) => {
+				let item_name_str = rename::label(item_name, item_config.rename.as_ref(), None);
+				let configs = field_configs(&item_struct.fields, &field_defaults, fmt_trait.attributes);
+				let newtype = if fmt_trait.forward_newtypes || item_config.transparent {
+					process_newtype(&item_struct.fields, &configs, &mut bounds, fmt_trait)
+				} else {
+					None
+				};
+				if let Some((destructure, implementation)) = newtype {
+					quote!(#[allow(unused_variables)] let Self #destructure = self; #implementation)
+				} else {
+					if item_config.transparent {
+						transparent_error(&[&item_config.keys], item_name.span(), &configs);
+					}
+					match item_struct.fields {
+						syn::Fields::Unit => process_unit(&item_name_str, fmt_trait),
+						syn::Fields::Unnamed(fields) => {
+							let (destructure, implementation) = process_tuple(
+								layout.tuple_builder(&item_name_str),
+								&fields,
+								&configs,
+								non_exhaustive.finish(&item.attrs, &configs),
+								&mut bounds,
+								fmt_trait,
+							);
+							quote!(#[allow(unused_variables)] let #item_name #destructure = self; #implementation)
+						}
+						syn::Fields::Named(fields) => {
+							let (destructure, implementation) = process_struct(
+								layout.struct_builder(&item_name_str),
+								&fields,
+								&configs,
+								item_config.rename_all,
+								non_exhaustive.finish(&item.attrs, &configs),
+								&mut bounds,
+								fmt_trait,
+							);
+							quote!(#[allow(unused_variables)] let #item_name #destructure = self; #implementation)
+						}
+					}
+				}
+			}
+			syn::Data::Union(item_union) => match &item_config.variant {
+				None if item_config.union_bytes => {
+					let item_name_str = rename::label(item_name, item_config.rename.as_ref(), None);
+					process_union_bytes(&item_name_str, &item_union.fields)
+				}
+				Some(selector) => {
+					let item_name_str = rename::label(item_name, item_config.rename.as_ref(), None);
+					let configs = field_configs(
+						&syn::Fields::Named(item_union.fields.clone()),
+						&field_defaults,
+						fmt_trait.attributes,
+					);
+					process_union(
+						&item_name_str,
+						&item_union.fields,
+						&configs,
+						selector,
+						&item_config,
+						&mut bounds,
+						fmt_trait,
+					)
+				}
+				None => {
+					let name = format!("<{}>", item.ident);
+					quote!(::core::write!(fmt_derive_formatter_variable, #name))
+				}
+			},
+			syn::Data::Enum(item_enum) => {
+				if item_enum.variants.is_empty() {
+					quote!(::core::unreachable!())
+				} else {
+					let mut stream = proc_macro2::TokenStream::new();
+
+					for variant in item_enum.variants {
+						let variant_name = variant.ident;
+						let variant_config: variant_attribute::VariantAttribute =
+							parse_attributes(&variant.attrs, fmt_trait.attributes);
+						let (destructure, implementation) = if let Some(format) = variant_config.format {
+							let format = format.expand(&variant.fields);
+							let destructure = match variant.fields {
+								syn::Fields::Unit => quote!(),
+								syn::Fields::Unnamed(fields) => {
+									let var_names = (0..fields.unnamed.len()).map(|field_number| {
+										proc_macro2::Ident::new(&format!("_{}", field_number), proc_macro2::Span::call_site())
+									});
+									quote!((#(#var_names,)*))
+								}
+								syn::Fields::Named(fields) => {
+									let var_names = fields.named.into_iter().map(|field| field.ident);
+									quote!({#(#var_names,)*})
+								}
+							};
+							(
+								destructure,
+								quote!(::core::write!(fmt_derive_formatter_variable, #format)),
+							)
+						} else {
+							let variant_name_str =
+								rename::label(&variant_name, variant_config.rename.as_ref(), item_config.rename_all);
+							let configs = field_configs(&variant.fields, &field_defaults, fmt_trait.attributes);
+
+							let transparent = variant_config.transparent || item_config.transparent;
+							let newtype = if fmt_trait.forward_newtypes || transparent {
+								process_newtype(&variant.fields, &configs, &mut bounds, fmt_trait)
+							} else {
+								None
+							};
+							if let Some(arm) = newtype {
+								arm
+							} else {
+								if transparent {
+									transparent_error(
+										&[&variant_config.keys, &item_config.keys],
+										variant_name.span(),
+										&configs,
+									);
+									continue;
+								}
+								match variant.fields {
+									syn::Fields::Unit => (quote!(), process_unit(&variant_name_str, fmt_trait)),
+									syn::Fields::Unnamed(fields) => process_tuple(
+										layout.tuple_builder(&variant_name_str),
+										&fields,
+										&configs,
+										non_exhaustive.finish(&variant.attrs, &configs),
+										&mut bounds,
+										fmt_trait,
+									),
+									syn::Fields::Named(fields) => process_struct(
+										layout.struct_builder(&variant_name_str),
+										&fields,
+										&configs,
+										variant_config.rename_all.or(item_config.rename_all),
+										non_exhaustive.finish(&variant.attrs, &configs),
+										&mut bounds,
+										fmt_trait,
+									),
+								}
+							}
+						};
+						let implementation = if variant_config.pad {
+							pad(implementation)
+						} else {
+							implementation
+						};
+						stream.extend(quote!(#[allow(unused_variables)] Self::#variant_name #destructure => { #implementation }));
+					}
+
+					quote!(match self { #stream })
+				}
+			}
+		},
+	};
+
+	let implementation = if item_config.pad {
+		pad(implementation)
+	} else {
+		implementation
+	};
+
+	let GenericVariants {
+		params_bare: generics_params_bare,
+		params_no_defaults: generics_params_no_defaults,
+		where_clause: generics_where,
+	} = GenericVariants::new(&bounds.apply(&item.generics));
+	let track_manifest = manifest::track();
+	let result = quote!(
+		impl<#generics_params_no_defaults> ::core::fmt::#trait_name for #item_name<#generics_params_bare> #generics_where {
+			fn fmt(&self, fmt_derive_formatter_variable: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+				#use_rt
+				#track_manifest
+				#implementation
+			}
+		}
+	);
+	// println!("{}", result);
+
+	abort_if_dirty();
+	result.into()
+}
+
+/// How a single field is printed.
+pub enum FieldOutput<'a> {
+	/// The field is not printed at all.
+	Ignored,
+	/// The field is printed by an expression of type `&impl Debug`.
+	Debug(proc_macro2::TokenStream),
+	/// The field is printed using the derived trait if its type implements it, or as a replacement string otherwise.
+	OrReplacement {
+		var_name: syn::Ident,
+		ty: &'a syn::Type,
+		opaque: proc_macro2::TokenStream,
+		/// The name of the field (or its index), which `strict` reports if the type does not implement the trait.
+		label: String,
+		strict: Strict,
+	},
+}
+
+impl<'a> FieldOutput<'a> {
+	pub fn new(
+		config: &field_attribute::FieldAttribute,
+		var_name: &syn::Ident,
+		field: &'a syn::Field,
+		scope: &syn::Fields,
+	) -> FieldOutput<'a> {
+		if config.ignore {
+			FieldOutput::Ignored
+		} else if let Some(redact) = &config.redact {
+			FieldOutput::Debug(redact.expand(var_name))
+		} else if let Some(with) = &config.with {
+			FieldOutput::Debug(quote!(&_rt::FormatWith(#var_name, #with)))
+		} else if let Some(format) = &config.format {
+			let format = format.expand(scope);
+			FieldOutput::Debug(quote!(&_rt::DebugDisplay(&::core::format_args!(#format))))
+		} else {
+			FieldOutput::OrReplacement {
+				var_name: var_name.clone(),
+				ty: &field.ty,
+				opaque: config.placeholder.clone().unwrap_or_default().expand(&field.ty),
+				label: match &field.ident {
+					Some(ident) => ident.unraw().to_string(),
+					None => var_name.to_string().trim_start_matches('_').to_owned(),
+				},
+				strict: config.strict.unwrap_or_default(),
+			}
+		}
+	}
+
+	/// Whether the field is printed using the derived trait (as opposed to being ignored or printed by custom code).
+	pub fn uses_trait(&self) -> bool {
+		matches!(self, FieldOutput::OrReplacement { .. })
+	}
+
+	fn tuple_field(&self, fmt_trait: &FmtTrait) -> proc_macro2::TokenStream {
+		match self {
+			FieldOutput::Ignored => quote!(),
+			FieldOutput::Debug(expr) => quote!(w.field(#expr);),
+			FieldOutput::OrReplacement {
+				var_name,
+				ty,
+				opaque,
+				label,
+				strict,
+			} => {
+				let or_replacement = fmt_trait.or_replacement();
+				let check = strict.check(label, ty, fmt_trait.name, &fmt_trait.path());
+				quote!(#check _rt::#or_replacement::<#ty>(&#var_name).tuple_field(#opaque, &mut w);)
+			}
+		}
+	}
+
+	fn struct_field(&self, name: &str, fmt_trait: &FmtTrait) -> proc_macro2::TokenStream {
+		match self {
+			FieldOutput::Ignored => quote!(),
+			FieldOutput::Debug(expr) => quote!(w.field(#name, #expr);),
+			FieldOutput::OrReplacement {
+				var_name,
+				ty,
+				opaque,
+				label,
+				strict,
+			} => {
+				let or_replacement = fmt_trait.or_replacement();
+				let check = strict.check(label, ty, fmt_trait.name, &fmt_trait.path());
+				quote!(#check _rt::#or_replacement::<#ty>(&#var_name).struct_field(#name, #opaque, &mut w);)
+			}
+		}
+	}
+
+	/// Formats the field as the whole output, passing on the formatter (and thereby all of its options).
+	fn forward(&self, fmt_trait: &FmtTrait) -> proc_macro2::TokenStream {
+		match self {
+			FieldOutput::Ignored => quote!(::core::result::Result::Ok(())),
+			FieldOutput::Debug(expr) => quote!(::core::fmt::Debug::fmt(#expr, fmt_derive_formatter_variable)),
+			FieldOutput::OrReplacement {
+				var_name,
+				ty,
+				opaque,
+				label,
+				strict,
+			} => {
+				let or_replacement = fmt_trait.or_replacement();
+				let check = strict.check(label, ty, fmt_trait.name, &fmt_trait.path());
+				quote!({ #check _rt::#or_replacement::<#ty>(&#var_name).forward(#opaque, fmt_derive_formatter_variable) })
+			}
+		}
+	}
+}
+
+/// The values that the item gives for all of its fields, i.e., the placeholder and the strictness (both falling back to
+/// the ones given by the manifest of the crate).
+pub fn field_defaults(item_config: &item_attribute::ItemAttribute) -> field_attribute::FieldAttribute {
+	let placeholder = match &item_config.placeholder {
+		Some(placeholder) => placeholder.clone(),
+		None => Placeholder::from_manifest().unwrap_or_else(|error| {
+			emit_error!(error);
+			Placeholder::default()
+		}),
+	};
+	field_attribute::FieldAttribute {
+		placeholder: Some(placeholder),
+		strict: Some(item_config.strict.unwrap_or_else(|| {
+			Strict::from_manifest().unwrap_or_else(|error| {
+				emit_error!(error);
+				Strict::default()
+			})
+		})),
+		..Default::default()
+	}
+}
+
+/// Parses the attributes of all fields on top of `defaults`, which holds the values given by the item.
+pub fn field_configs(
+	fields: &syn::Fields,
+	defaults: &field_attribute::FieldAttribute,
+	attributes: &[&str],
+) -> Vec<field_attribute::FieldAttribute> {
+	fields
+		.iter()
+		.map(|field| {
+			let mut config = defaults.clone();
+			config.update(parse_attributes(&field.attrs, attributes));
+			config
+		})
+		.collect()
+}
+
+/// The variable a field is destructured into: its name for named fields and `_0`, `_1`, ... for tuple fields.
+pub fn field_var_name(field_number: usize, field: &syn::Field) -> syn::Ident {
+	match &field.ident {
+		Some(ident) => ident.clone(),
+		None => proc_macro2::Ident::new(&format!("_{}", field_number), proc_macro2::Span::call_site()),
+	}
+}
+
+/// Guards the code that prints a field so that `skipped` runs instead if the predicate given by `skip_if` returns
+/// `true` for the field at runtime.
+pub fn skip_if(
+	config: &field_attribute::FieldAttribute,
+	var_name: &syn::Ident,
+	printed: proc_macro2::TokenStream,
+	skipped: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+	match &config.skip_if {
+		Some(predicate) if !config.ignore => quote!(if #predicate(#var_name) { #skipped } else { #printed }),
+		_ => printed,
+	}
+}
+
+/// Forwards to the only printed field of a `struct` or variant, passing on the formatter (and thereby all of its
+/// options). Returns the destructuring pattern and the implementation, or `None` if there is not exactly one printed
+/// field.
+fn process_newtype(
+	fields: &syn::Fields,
+	configs: &[field_attribute::FieldAttribute],
+	bounds: &mut Bounds,
+	fmt_trait: &FmtTrait,
+) -> Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+	if configs.iter().filter(|config| !config.ignore).count() != 1 {
+		return None;
+	}
+
+	let mut implementation = None;
+	let mut destructure = proc_macro2::TokenStream::new();
+	for ((field_number, field), config) in fields.iter().enumerate().zip(configs) {
+		let var_name = field_var_name(field_number, field);
+		destructure.extend(quote!(#var_name,));
+		if !config.ignore {
+			let output = FieldOutput::new(config, &var_name, field, fields);
+			bounds.add_field(field, config.bound.as_ref(), output.uses_trait());
+			implementation = Some(skip_if(
+				config,
+				&var_name,
+				output.forward(fmt_trait),
+				// the width and fill still apply to the empty output, like they would for the field
+				quote!(fmt_derive_formatter_variable.pad("")),
+			));
+		}
+	}
+
+	let implementation = implementation?;
+	let destructure = match fields {
+		syn::Fields::Unit => return None,
+		syn::Fields::Unnamed(_) => quote!((#destructure)),
+		syn::Fields::Named(_) => quote!({#destructure}),
+	};
+	Some((destructure, quote!(use _rt::Replacement; #implementation)))
+}
+
+/// Wraps an implementation so that the width, fill, alignment and precision of the formatter apply to its whole
+/// output, as given by `pad`.
+fn pad(implementation: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	quote!(_rt::pad(fmt_derive_formatter_variable, |fmt_derive_formatter_variable| { #implementation }))
+}
+
+/// Reports the keys given inside of `unsafe(...)`, which print a `union` by reading its memory and are therefore only
+/// supported by the derives of the `core::fmt` traits, for the derive called `derive`.
+pub fn reject_unsafe_keys(item_config: &item_attribute::ItemAttribute, derive: &str) {
+	for key in item_config.unsafe_keys() {
+		emit_error!(key, "`{}` cannot be used with `{}`", key, derive);
+	}
+}
+
+/// Reports that `transparent` was given for a `struct` or variant that does not have exactly one printed field.
+pub fn transparent_error(
+	keys: &[&[syn::Ident]],
+	fallback: proc_macro2::Span,
+	configs: &[field_attribute::FieldAttribute],
+) {
+	let span = keys
+		.iter()
+		.flat_map(|keys| keys.iter())
+		.find(|key| *key == "transparent")
+		.map_or(fallback, |key| key.span());
+	let printed = configs.iter().filter(|config| !config.ignore).count();
+	emit_error!(
+		span, "`transparent` requires exactly one field that is not ignored, but there are {}", printed;
+		help = "use `ignore` on all other fields"
+	);
+}
+
+fn process_unit(name: &str, fmt_trait: &FmtTrait) -> proc_macro2::TokenStream {
+	if fmt_trait.plain_units {
+		quote!(fmt_derive_formatter_variable.pad(#name))
+	} else {
+		quote!(fmt_derive_formatter_variable.debug_struct(#name).finish())
+	}
+}
+
+/// Prints the fields of a tuple `struct` or variant, starting with `builder` and ending with `finish`.
+fn process_tuple(
+	builder: proc_macro2::TokenStream,
+	fields: &syn::FieldsUnnamed,
+	configs: &[field_attribute::FieldAttribute],
+	finish: proc_macro2::TokenStream,
+	bounds: &mut Bounds,
+	fmt_trait: &FmtTrait,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	let scope = syn::Fields::Unnamed(fields.clone());
+	let mut destructure = proc_macro2::TokenStream::new();
+	let mut chain = quote!(use _rt::Replacement; let mut w = #builder;);
+
+	for ((field_number, field), config) in fields.unnamed.iter().enumerate().zip(configs) {
+		let var_name = field_var_name(field_number, field);
+		destructure.extend(quote!(#var_name,));
+
+		let output = FieldOutput::new(config, &var_name, field, &scope);
+		bounds.add_field(field, config.bound.as_ref(), output.uses_trait());
+		chain.extend(skip_if(config, &var_name, output.tuple_field(fmt_trait), quote!()));
+	}
+
+	(quote!((#destructure)), quote!(#chain #finish))
+}
+
+/// Prints the fields of a `struct` or variant with named fields, starting with `builder` and ending with `finish`.
+fn process_struct(
+	builder: proc_macro2::TokenStream,
+	fields: &syn::FieldsNamed,
+	configs: &[field_attribute::FieldAttribute],
+	rename_all: Option<rename::RenameRule>,
+	finish: proc_macro2::TokenStream,
+	bounds: &mut Bounds,
+	fmt_trait: &FmtTrait,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+	let scope = syn::Fields::Named(fields.clone());
+	let mut destructure = proc_macro2::TokenStream::new();
+	let mut chain = quote!(use _rt::Replacement; let mut w = #builder;);
+
+	for (field, config) in fields.named.iter().zip(configs) {
+		let field_name = field.ident.as_ref().expect("a named field should always have a name");
+		destructure.extend(quote!(#field_name,));
+
+		let field_name_str = rename::label(field_name, config.rename.as_ref(), rename_all);
+		let output = FieldOutput::new(config, field_name, field, &scope);
+		bounds.add_field(field, config.bound.as_ref(), output.uses_trait());
+		chain.extend(skip_if(
+			config,
+			field_name,
+			output.struct_field(&field_name_str, fmt_trait),
+			quote!(),
+		));
+	}
+
+	(quote!({#destructure}), quote!(#chain #finish))
+}
+
+/// Prints the field of a `union` that the selector given by `unsafe(variant = ...)` names, as if the `union` were a
+/// `struct` with only that field. A selector that is a path is called with `self`, any other expression is used as is.
+/// Either way, its result is compared with the (unrenamed) names of the fields.
+fn process_union(
+	name: &str,
+	fields: &syn::FieldsNamed,
+	configs: &[field_attribute::FieldAttribute],
+	selector: &syn::Expr,
+	item_config: &item_attribute::ItemAttribute,
+	bounds: &mut Bounds,
+	fmt_trait: &FmtTrait,
+) -> proc_macro2::TokenStream {
+	let selector = match selector {
+		syn::Expr::Path(path) => quote!(#path(self)),
+		expr => quote!(#expr),
+	};
+
+	let mut arms = proc_macro2::TokenStream::new();
+	for (field, config) in fields.named.iter().zip(configs) {
+		let field_name = field.ident.as_ref().expect("a named field should always have a name");
+		let field_name_str = field_name.unraw().to_string();
+		let scope = syn::Fields::Named(syn::FieldsNamed {
+			brace_token: fields.brace_token,
+			named: core::iter::once(field.clone()).collect(),
+		});
+
+		let output = FieldOutput::new(config, field_name, field, &scope);
+		bounds.add_field(field, config.bound.as_ref(), output.uses_trait());
+		let implementation = if fmt_trait.forward_newtypes {
+			skip_if(
+				config,
+				field_name,
+				output.forward(fmt_trait),
+				// the width and fill still apply to the empty output, like they would for the field
+				quote!(fmt_derive_formatter_variable.pad("")),
+			)
+		} else {
+			let field_output = skip_if(
+				config,
+				field_name,
+				output.struct_field(
+					&rename::label(field_name, config.rename.as_ref(), item_config.rename_all),
+					fmt_trait,
+				),
+				quote!(),
+			);
+			let builder = item_config.layout.unwrap_or_default().struct_builder(name);
+			let finish = item_config
+				.non_exhaustive
+				.unwrap_or_default()
+				.finish(&[], core::slice::from_ref(config));
+			quote!(let mut w = #builder; #field_output #finish)
+		};
+		arms.extend(quote!(#field_name_str => {
+			// SAFETY: the user promised with `unsafe(variant = ...)` that the selector names the active field
+			#[allow(unused_variables)]
+			let #field_name = unsafe { &self.#field_name };
+			#implementation
+		}));
+	}
+
+	let opaque = format!("<{}>", name);
+	quote! {
+		use _rt::Replacement;
+		match ::core::convert::AsRef::<str>::as_ref(&#selector) {
+			#arms
+			_ => ::core::write!(fmt_derive_formatter_variable, #opaque),
+		}
+	}
+}
+
+/// Prints the memory of a `union` as a list of bytes, as given by `unsafe(union_bytes)`.
+fn process_union_bytes(name: &str, fields: &syn::FieldsNamed) -> proc_macro2::TokenStream {
+	let types = fields.named.iter().map(|field| &field.ty);
+	quote! {
+		#(const { _rt::assert_spans_union::<#types, Self>() };)*
+		// SAFETY: every field spans the whole union without padding, so all of its bytes are initialized no matter
+		// which field it was created through
+		let bytes = unsafe {
+			::core::slice::from_raw_parts((self as *const Self).cast::<u8>(), ::core::mem::size_of::<Self>())
+		};
+		fmt_derive_formatter_variable.debug_struct(#name).field("bytes", &_rt::HexBytes(bytes)).finish()
+	}
+}
@@ -0,0 +1,125 @@
+use proc_macro_error2::{abort_if_dirty, emit_error};
+use quote::quote;
+use syn::parse_macro_input;
+
+use crate::generics::GenericVariants;
+use crate::syntax::{error_attribute, parse_attributes};
+
+pub fn error(item: proc_macro::TokenStream, use_rt: &proc_macro2::TokenStream) -> proc_macro::TokenStream {
+	let item = parse_macro_input!(item as syn::DeriveInput);
+	let item_name = &item.ident;
+
+	let GenericVariants {
+		params_bare: generics_params_bare,
+		params_no_defaults: generics_params_no_defaults,
+		where_clause: generics_where,
+	} = GenericVariants::new(&item.generics);
+
+	let mut from_impls = proc_macro2::TokenStream::new();
+	let mut add_from_impl = |constructor: proc_macro2::TokenStream, source: &SourceField| {
+		let SourceField { member, ty, .. } = source;
+		from_impls.extend(quote!(
+			impl<#generics_params_no_defaults> ::core::convert::From<#ty>
+				for #item_name<#generics_params_bare> #generics_where
+			{
+				fn from(source: #ty) -> Self {
+					#constructor { #member: source }
+				}
+			}
+		));
+	};
+
+	let source = match &item.data {
+		syn::Data::Struct(item_struct) => match source_field(&item_struct.fields) {
+			Some(source) => {
+				if source.from {
+					add_from_impl(quote!(Self), &source);
+				}
+				let member = &source.member;
+				quote!(
+					let Self { #member: source, .. } = self;
+					::core::option::Option::Some(source.as_dyn_error())
+				)
+			}
+			None => quote!(::core::option::Option::None),
+		},
+		syn::Data::Enum(item_enum) => {
+			let mut stream = proc_macro2::TokenStream::new();
+			for variant in &item_enum.variants {
+				let variant_name = &variant.ident;
+				if let Some(source) = source_field(&variant.fields) {
+					if source.from {
+						add_from_impl(quote!(Self::#variant_name), &source);
+					}
+					let member = &source.member;
+					stream.extend(quote!(
+						Self::#variant_name { #member: source, .. } => ::core::option::Option::Some(source.as_dyn_error()),
+					));
+				}
+			}
+			quote!(
+				match self {
+					#stream
+					#[allow(unreachable_patterns)]
+					_ => ::core::option::Option::None,
+				}
+			)
+		}
+		syn::Data::Union(_) => quote!(::core::option::Option::None),
+	};
+
+	let result = quote!(
+		impl<#generics_params_no_defaults> ::core::error::Error for #item_name<#generics_params_bare> #generics_where {
+			fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+				#use_rt
+				use _rt::AsDynError;
+				#source
+			}
+		}
+		#from_impls
+	);
+
+	abort_if_dirty();
+	result.into()
+}
+
+struct SourceField {
+	member: syn::Member,
+	ty: syn::Type,
+	from: bool,
+}
+
+/// Finds the field marked with `#[error(source)]` or `#[error(from)]`, if any.
+fn source_field(fields: &syn::Fields) -> Option<SourceField> {
+	let mut result: Option<SourceField> = None;
+
+	for (field_number, field) in fields.iter().enumerate() {
+		let config: error_attribute::ErrorAttribute = parse_attributes(&field.attrs, &["error"]);
+
+		if !config.source && !config.from {
+			continue;
+		}
+		if config.from && fields.len() != 1 {
+			emit_error!(
+				field, "`#[error(from)]` can only be used on the only field of a struct or variant";
+				help = "use `#[error(source)]` to mark the field as the source without deriving `From`"
+			);
+		}
+		if result.is_some() {
+			emit_error!(field, "only one field can be marked as the error source");
+			continue;
+		}
+
+		let member = match &field.ident {
+			Some(ident) => syn::Member::Named(ident.clone()),
+			None => syn::Member::Unnamed(syn::Index::from(field_number)),
+		};
+		result = Some(SourceField {
+			member,
+			ty: field.ty.clone(),
+			from: config.from,
+		});
+	}
+
+	result
+}
@@ -0,0 +1,88 @@
+/// One of the formatting traits from `core::fmt` that can be derived.
+#[derive(Clone, Copy, Debug)]
+pub struct FmtTrait {
+	/// The name of the trait in `core::fmt`.
+	pub name: &'static str,
+	/// The attributes that configure the derived implementation of this trait, in ascending order of precedence.
+	pub attributes: &'static [&'static str],
+	/// Whether a `struct` or variant with exactly one printed field is formatted exactly like that field. This is the
+	/// expected behavior for the numeric formatting traits, which are usually derived for newtypes.
+	pub forward_newtypes: bool,
+	/// Whether unit `struct`s and unit variants are printed as their plain (possibly renamed) name, which respects the
+	/// width, fill and alignment of the formatter like a `str` does.
+	pub plain_units: bool,
+}
+
+impl FmtTrait {
+	pub const DEBUG: FmtTrait = FmtTrait {
+		name: "Debug",
+		attributes: &["fmt", "debug"],
+		forward_newtypes: false,
+		plain_units: false,
+	};
+	pub const DISPLAY: FmtTrait = FmtTrait {
+		name: "Display",
+		attributes: &["fmt", "display"],
+		forward_newtypes: false,
+		plain_units: true,
+	};
+	pub const LOWER_HEX: FmtTrait = FmtTrait {
+		name: "LowerHex",
+		attributes: &["lower_hex"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+	pub const UPPER_HEX: FmtTrait = FmtTrait {
+		name: "UpperHex",
+		attributes: &["upper_hex"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+	pub const BINARY: FmtTrait = FmtTrait {
+		name: "Binary",
+		attributes: &["binary"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+	pub const OCTAL: FmtTrait = FmtTrait {
+		name: "Octal",
+		attributes: &["octal"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+	pub const LOWER_EXP: FmtTrait = FmtTrait {
+		name: "LowerExp",
+		attributes: &["lower_exp"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+	pub const UPPER_EXP: FmtTrait = FmtTrait {
+		name: "UpperExp",
+		attributes: &["upper_exp"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+	pub const POINTER: FmtTrait = FmtTrait {
+		name: "Pointer",
+		attributes: &["pointer"],
+		forward_newtypes: true,
+		plain_units: false,
+	};
+
+	/// The trait itself, e.g., `Debug`.
+	pub fn ident(&self) -> syn::Ident {
+		syn::Ident::new(self.name, proc_macro2::Span::call_site())
+	}
+
+	/// The path of the trait, e.g., `::core::fmt::Debug`.
+	pub fn path(&self) -> proc_macro2::TokenStream {
+		let ident = self.ident();
+		quote::quote!(::core::fmt::#ident)
+	}
+
+	/// The runtime type that prints a value using this trait if possible and a replacement string otherwise, e.g.,
+	/// `DebugOrReplacement`.
+	pub fn or_replacement(&self) -> syn::Ident {
+		quote::format_ident!("{}OrReplacement", self.name)
+	}
+}
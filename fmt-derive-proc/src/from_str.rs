@@ -0,0 +1,270 @@
+use proc_macro_error2::{abort_if_dirty, emit_error};
+use quote::{format_ident, quote};
+use syn::parse_macro_input;
+
+use crate::fmt_trait::FmtTrait;
+use crate::generics::{Bounds, GenericVariants};
+use crate::rename;
+use crate::syntax::format::{Format, Segment};
+use crate::syntax::{field_attribute, item_attribute, parse_attributes, variant_attribute};
+
+pub fn from_str(item: proc_macro::TokenStream, crate_path: &proc_macro2::TokenStream) -> proc_macro::TokenStream {
+	let item = parse_macro_input!(item as syn::DeriveInput);
+	let item_name = &item.ident;
+	let attributes = FmtTrait::DISPLAY.attributes;
+
+	let item_config: item_attribute::ItemAttribute = parse_attributes(&item.attrs, attributes);
+	abort_if_dirty();
+
+	// `bound` is meant for the derived formatting trait, so the bounds of `FromStr` are always inferred
+	let mut bounds = FieldBounds {
+		from_str: Bounds::new(&item.generics, quote!(::core::str::FromStr), None, true),
+		default: Bounds::new(&item.generics, quote!(::core::default::Default), None, true),
+	};
+
+	let implementation = match &item.data {
+		syn::Data::Struct(item_struct) => {
+			let configs = field_configs(&item_struct.fields, attributes);
+			let name = rename::label(item_name, item_config.rename.as_ref(), None);
+			match &item_config.format {
+				Some(format) => process_format(quote!(Self), format, &item_struct.fields, &mut bounds),
+				None => process_plain(
+					quote!(Self),
+					&name,
+					&item_struct.fields,
+					&configs,
+					FmtTrait::DISPLAY.forward_newtypes || item_config.transparent,
+					&mut bounds,
+				),
+			}
+		}
+		syn::Data::Enum(item_enum) => {
+			if let Some(format) = &item_config.format {
+				emit_error!(
+					format.string, "`FromStr` cannot be derived for an `enum` with a single format for all variants";
+					help = "give each variant its own format instead"
+				);
+			}
+			let mut parsers = proc_macro2::TokenStream::new();
+			for variant in &item_enum.variants {
+				let variant_name = &variant.ident;
+				let variant_config: variant_attribute::VariantAttribute = parse_attributes(&variant.attrs, attributes);
+				let configs = field_configs(&variant.fields, attributes);
+				let name = rename::label(variant_name, variant_config.rename.as_ref(), item_config.rename_all);
+				parsers.extend(match &variant_config.format {
+					Some(format) => process_format(quote!(Self::#variant_name), format, &variant.fields, &mut bounds),
+					None => process_plain(
+						quote!(Self::#variant_name),
+						&name,
+						&variant.fields,
+						&configs,
+						variant_config.transparent || item_config.transparent,
+						&mut bounds,
+					),
+				});
+			}
+			parsers
+		}
+		syn::Data::Union(_) => {
+			emit_error!(item_name, "`FromStr` cannot be derived for a `union`");
+			quote!()
+		}
+	};
+
+	let generics = bounds.default.apply(&bounds.from_str.apply(&item.generics));
+	let GenericVariants {
+		params_bare: generics_params_bare,
+		params_no_defaults: generics_params_no_defaults,
+		where_clause: generics_where,
+	} = GenericVariants::new(&generics);
+	let type_name = item_name.to_string();
+	let result = quote!(
+		impl<#generics_params_no_defaults> ::core::str::FromStr for #item_name<#generics_params_bare> #generics_where {
+			type Err = #crate_path::_rt::ParseError;
+
+			fn from_str(fmt_derive_input: &str) -> ::core::result::Result<Self, Self::Err> {
+				use #crate_path::_rt;
+				#implementation
+				::core::result::Result::Err(_rt::ParseError::new(#type_name))
+			}
+		}
+	);
+
+	abort_if_dirty();
+	result.into()
+}
+
+/// The bounds for fields that are parsed and for fields that are filled in with their default value, respectively.
+struct FieldBounds {
+	from_str: Bounds,
+	default: Bounds,
+}
+
+fn field_configs(fields: &syn::Fields, attributes: &[&str]) -> Vec<field_attribute::FieldAttribute> {
+	fields
+		.iter()
+		.map(|field| parse_attributes(&field.attrs, attributes))
+		.collect()
+}
+
+/// Expands to `constructor` applied to `values`, which are given in the order of `fields`.
+fn construct(
+	constructor: &proc_macro2::TokenStream,
+	fields: &syn::Fields,
+	values: Vec<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+	match fields {
+		syn::Fields::Unit => quote!(#constructor),
+		syn::Fields::Unnamed(_) => quote!(#constructor(#(#values),*)),
+		syn::Fields::Named(_) => {
+			let names = fields.iter().map(|field| &field.ident);
+			quote!(#constructor { #(#names: #values),* })
+		}
+	}
+}
+
+/// Parses the output of a format string by matching its literal text and parsing each field that it refers to.
+fn process_format(
+	constructor: proc_macro2::TokenStream,
+	format: &Format,
+	fields: &syn::Fields,
+	bounds: &mut FieldBounds,
+) -> proc_macro2::TokenStream {
+	let segments = match format.segments(fields) {
+		Ok(segments) => segments,
+		Err(error) => {
+			emit_error!(error);
+			return quote!();
+		}
+	};
+
+	// The fields in the order in which the format string refers to them.
+	let mut referenced = Vec::<usize>::new();
+	let mut matcher = Vec::new();
+	for segment in segments {
+		match segment {
+			Segment::Literal(text) => matcher.push(quote!(_rt::Segment::Literal(#text))),
+			Segment::Field(index) => {
+				if referenced.contains(&index) {
+					let field = fields
+						.iter()
+						.nth(index)
+						.expect("`segments` only returns valid field indices");
+					let name = field
+						.ident
+						.as_ref()
+						.map_or_else(|| index.to_string(), |ident| ident.to_string());
+					emit_error!(
+						format.string,
+						"`FromStr` cannot parse field `{}`, as the format string refers to it more than once",
+						name
+					);
+					continue;
+				}
+				let slot = referenced.len();
+				referenced.push(index);
+				matcher.push(quote!(_rt::Segment::Field(#slot)));
+			}
+		}
+	}
+
+	let variable = |index: usize| format_ident!("fmt_derive_field_{}", index);
+	let mut declarations = proc_macro2::TokenStream::new();
+	let mut parsers = Vec::new();
+	for &index in &referenced {
+		let field = fields
+			.iter()
+			.nth(index)
+			.expect("`segments` only returns valid field indices");
+		let ty = &field.ty;
+		let variable = variable(index);
+		bounds.from_str.add_field(field, None, true);
+		declarations.extend(quote!(let mut #variable = ::core::option::Option::<#ty>::None;));
+		parsers.push(
+			quote!(&mut |text: &str| match <#ty as ::core::str::FromStr>::from_str(text) {
+				::core::result::Result::Ok(value) => {
+					#variable = ::core::option::Option::Some(value);
+					true
+				}
+				::core::result::Result::Err(_) => false,
+			}),
+		);
+	}
+
+	let values = fields
+		.iter()
+		.enumerate()
+		.map(|(index, field)| {
+			if referenced.contains(&index) {
+				let variable = variable(index);
+				quote!(#variable.expect("a matched field has been parsed"))
+			} else {
+				bounds.default.add_field(field, None, true);
+				quote!(::core::default::Default::default())
+			}
+		})
+		.collect();
+	let value = construct(&constructor, fields, values);
+
+	quote! {{
+		#declarations
+		if _rt::match_segments(fmt_derive_input, &[#(#matcher),*], &mut [#(#parsers),*]) {
+			return ::core::result::Result::Ok(#value);
+		}
+	}}
+}
+
+/// Parses the output of a `struct` or variant without a format string, which is either its plain name (for units) or,
+/// if it is transparent, the output of its only printed field.
+fn process_plain(
+	constructor: proc_macro2::TokenStream,
+	name: &str,
+	fields: &syn::Fields,
+	configs: &[field_attribute::FieldAttribute],
+	transparent: bool,
+	bounds: &mut FieldBounds,
+) -> proc_macro2::TokenStream {
+	if let syn::Fields::Unit = fields {
+		return quote! {
+			if fmt_derive_input == #name {
+				return ::core::result::Result::Ok(#constructor);
+			}
+		};
+	}
+
+	let printed = configs.iter().filter(|config| !config.ignore).count();
+	if !transparent || printed != 1 {
+		emit_error!(
+			fields, "`FromStr` cannot parse `{}`, as it is not printed with a format string", name;
+			help = "add a format string such as `#[display(\"...\")]`, which `FromStr` parses"
+		);
+		return quote!();
+	}
+
+	let mut parser = proc_macro2::TokenStream::new();
+	let mut values = Vec::new();
+	for (field, config) in fields.iter().zip(configs) {
+		if config.ignore {
+			bounds.default.add_field(field, None, true);
+			values.push(quote!(::core::default::Default::default()));
+		} else if config.redact.is_some() || config.with.is_some() || config.format.is_some() {
+			emit_error!(
+				field, "`FromStr` cannot parse a field that is printed with `redact`, `with` or a format string";
+				help = "give the `struct` or variant a format string instead"
+			);
+			return quote!();
+		} else {
+			bounds.from_str.add_field(field, None, true);
+			let ty = &field.ty;
+			parser = quote!(<#ty as ::core::str::FromStr>::from_str(fmt_derive_input));
+			values.push(quote!(value));
+		}
+	}
+	let value = construct(&constructor, fields, values);
+
+	quote! {
+		if let ::core::result::Result::Ok(value) = #parser {
+			return ::core::result::Result::Ok(#value);
+		}
+	}
+}
@@ -1,5 +1,7 @@
 use quote::quote;
 
+use crate::syntax::Bound;
+
 #[derive(Debug, Clone)]
 pub struct GenericVariants {
 	pub params_bare: proc_macro2::TokenStream,
@@ -73,3 +75,68 @@ impl GenericVariants {
 		}
 	}
 }
+
+/// Collects the additional where predicates required by a derived implementation.
+pub struct Bounds {
+	trait_path: proc_macro2::TokenStream,
+	type_params: Vec<syn::Ident>,
+	infer: bool,
+	predicates: Vec<syn::WherePredicate>,
+}
+
+impl Bounds {
+	/// An explicit item-level `bound` replaces all inferred bounds.
+	pub fn new(
+		generics: &syn::Generics,
+		trait_path: proc_macro2::TokenStream,
+		bound: Option<&Bound>,
+		infer: bool,
+	) -> Bounds {
+		Bounds {
+			trait_path,
+			type_params: generics.type_params().map(|param| param.ident.clone()).collect(),
+			infer: infer && bound.is_none(),
+			predicates: bound.into_iter().flatten().cloned().collect(),
+		}
+	}
+
+	/// Adds the explicit `bound` of a field or, if bounds are inferred and the field is printed using the derived trait,
+	/// `T: Trait` for every type parameter `T` that occurs in the type of the field.
+	pub fn add_field(&mut self, field: &syn::Field, bound: Option<&Bound>, printed: bool) {
+		if let Some(bound) = bound {
+			self.predicates.extend(bound.iter().cloned());
+		} else if self.infer && printed {
+			let ty = &field.ty;
+			let mut mentioned = Vec::new();
+			collect_idents(quote!(#ty), &mut mentioned);
+			for param in &self.type_params {
+				if mentioned.contains(param) {
+					let trait_path = &self.trait_path;
+					let predicate: syn::WherePredicate = syn::parse_quote!(#param: #trait_path);
+					if !self.predicates.contains(&predicate) {
+						self.predicates.push(predicate);
+					}
+				}
+			}
+		}
+	}
+
+	/// Returns `generics` with all collected predicates added to its where clause.
+	pub fn apply(self, generics: &syn::Generics) -> syn::Generics {
+		let mut generics = generics.clone();
+		if !self.predicates.is_empty() {
+			generics.make_where_clause().predicates.extend(self.predicates);
+		}
+		generics
+	}
+}
+
+fn collect_idents(tokens: proc_macro2::TokenStream, idents: &mut Vec<syn::Ident>) {
+	for token in tokens {
+		match token {
+			proc_macro2::TokenTree::Ident(ident) => idents.push(ident),
+			proc_macro2::TokenTree::Group(group) => collect_idents(group.stream(), idents),
+			proc_macro2::TokenTree::Punct(_) | proc_macro2::TokenTree::Literal(_) => (),
+		}
+	}
+}
@@ -0,0 +1,237 @@
+use proc_macro_error2::abort_if_dirty;
+use quote::quote;
+use syn::parse_macro_input;
+
+use crate::derive::{
+	FieldOutput, field_configs, field_defaults, field_var_name, reject_unsafe_keys, skip_if, transparent_error,
+};
+use crate::generics::{Bounds, GenericVariants};
+use crate::syntax::field_attribute::FieldAttribute;
+use crate::syntax::format::Format;
+use crate::syntax::{item_attribute, parse_attributes, variant_attribute};
+use crate::{manifest, rename};
+
+/// The attributes that configure the derived implementation of `visit::Inspect`, in ascending order of precedence. It
+/// reports the same fields that `Debug` prints, so the attributes of `Debug` apply as well.
+pub const ATTRIBUTES: &[&str] = &["fmt", "debug", "inspect"];
+
+pub fn inspect(item: proc_macro::TokenStream, crate_path: &proc_macro2::TokenStream) -> proc_macro::TokenStream {
+	let item = parse_macro_input!(item as syn::DeriveInput);
+	let item_name = &item.ident;
+
+	let item_config: item_attribute::ItemAttribute = parse_attributes(&item.attrs, ATTRIBUTES);
+	reject_unsafe_keys(&item_config, "Inspect");
+	let field_defaults = field_defaults(&item_config);
+	abort_if_dirty();
+
+	let mut bounds = Bounds::new(
+		&item.generics,
+		quote!(#crate_path::_rt::Inspect),
+		item_config.bound.as_ref(),
+		item_config.infer_bounds,
+	);
+
+	let implementation = match &item_config.format {
+		Some(format) => {
+			let fields = match &item.data {
+				syn::Data::Struct(item_struct) => item_struct.fields.clone(),
+				syn::Data::Enum(_) | syn::Data::Union(_) => syn::Fields::Unit,
+			};
+			let destructure = match &fields {
+				syn::Fields::Unit => quote!(),
+				fields => {
+					let var_names = fields
+						.iter()
+						.enumerate()
+						.map(|(field_number, field)| field_var_name(field_number, field));
+					match fields {
+						syn::Fields::Named(_) => quote!(#[allow(unused_variables)] let Self { #(#var_names,)* } = self;),
+						_ => quote!(#[allow(unused_variables)] let Self(#(#var_names,)*) = self;),
+					}
+				}
+			};
+			let visit = visit_format(format, &fields);
+			quote!(#destructure #visit)
+		}
+		None => match &item.data {
+			syn::Data::Struct(item_struct) => {
+				let name = rename::label(item_name, item_config.rename.as_ref(), None);
+				let configs = field_configs(&item_struct.fields, &field_defaults, ATTRIBUTES);
+				let (destructure, implementation) = process_fields(
+					&name,
+					&item_struct.fields,
+					&configs,
+					item_config.rename_all,
+					item_config.transparent,
+					&mut bounds,
+				);
+				if item_config.transparent && implementation.is_none() {
+					transparent_error(&[&item_config.keys], item_name.span(), &configs);
+				}
+				quote!(#[allow(unused_variables)] let Self #destructure = self; #implementation)
+			}
+			syn::Data::Enum(item_enum) => {
+				let mut stream = proc_macro2::TokenStream::new();
+				for variant in &item_enum.variants {
+					let variant_name = &variant.ident;
+					let variant_config: variant_attribute::VariantAttribute = parse_attributes(&variant.attrs, ATTRIBUTES);
+					let (destructure, implementation) = match &variant_config.format {
+						Some(format) => {
+							let var_names = variant
+								.fields
+								.iter()
+								.enumerate()
+								.map(|(field_number, field)| field_var_name(field_number, field));
+							let destructure = match &variant.fields {
+								syn::Fields::Unit => quote!(),
+								syn::Fields::Unnamed(_) => quote!((#(#var_names,)*)),
+								syn::Fields::Named(_) => quote!({#(#var_names,)*}),
+							};
+							(destructure, Some(visit_format(format, &variant.fields)))
+						}
+						None => {
+							let name = rename::label(variant_name, variant_config.rename.as_ref(), item_config.rename_all);
+							let configs = field_configs(&variant.fields, &field_defaults, ATTRIBUTES);
+							let transparent = variant_config.transparent || item_config.transparent;
+							let (destructure, implementation) = process_fields(
+								&name,
+								&variant.fields,
+								&configs,
+								variant_config.rename_all.or(item_config.rename_all),
+								transparent,
+								&mut bounds,
+							);
+							if transparent && implementation.is_none() {
+								transparent_error(
+									&[&variant_config.keys, &item_config.keys],
+									variant_name.span(),
+									&configs,
+								);
+							}
+							(destructure, implementation)
+						}
+					};
+					stream.extend(quote!(#[allow(unused_variables)] Self::#variant_name #destructure => { #implementation }));
+				}
+				if item_enum.variants.is_empty() {
+					quote!(match *self {})
+				} else {
+					quote!(match self { #stream })
+				}
+			}
+			syn::Data::Union(_) => {
+				let name = format!("<{}>", item_name);
+				quote!(fmt_derive_visitor.visit_placeholder(&#name);)
+			}
+		},
+	};
+
+	let GenericVariants {
+		params_bare: generics_params_bare,
+		params_no_defaults: generics_params_no_defaults,
+		where_clause: generics_where,
+	} = GenericVariants::new(&bounds.apply(&item.generics));
+	let track_manifest = manifest::track();
+	let result = quote!(
+		impl<#generics_params_no_defaults> #crate_path::_rt::Inspect for #item_name<#generics_params_bare> #generics_where {
+			fn inspect(&self, fmt_derive_visitor: &mut dyn #crate_path::_rt::Visitor) {
+				use #crate_path::_rt;
+				#track_manifest
+				use _rt::Replacement;
+				#implementation
+			}
+		}
+	);
+
+	abort_if_dirty();
+	result.into()
+}
+
+/// Reports the fields of a `struct` or variant named `name`. Returns the destructuring pattern and the implementation,
+/// which is `None` if the fields are `transparent` but there is not exactly one visited field.
+fn process_fields(
+	name: &str,
+	fields: &syn::Fields,
+	configs: &[FieldAttribute],
+	rename_all: Option<rename::RenameRule>,
+	transparent: bool,
+	bounds: &mut Bounds,
+) -> (proc_macro2::TokenStream, Option<proc_macro2::TokenStream>) {
+	let mut destructure = proc_macro2::TokenStream::new();
+	let mut visits = Vec::new();
+	for ((field_number, field), config) in fields.iter().enumerate().zip(configs) {
+		let var_name = field_var_name(field_number, field);
+		destructure.extend(quote!(#var_name,));
+
+		let output = FieldOutput::new(config, &var_name, field, fields);
+		bounds.add_field(field, config.bound.as_ref(), output.uses_trait());
+		if let Some(visit) = visit_field(&output) {
+			let label = match &field.ident {
+				Some(ident) => rename::label(ident, config.rename.as_ref(), rename_all),
+				None => visits.len().to_string(),
+			};
+			visits.push((label, config, var_name, visit));
+		}
+	}
+	let destructure = match fields {
+		syn::Fields::Unit => quote!(),
+		syn::Fields::Unnamed(_) => quote!((#destructure)),
+		syn::Fields::Named(_) => quote!({#destructure}),
+	};
+
+	if transparent {
+		return match visits.as_slice() {
+			[(_, config, var_name, visit)] => (destructure, Some(skip_if(config, var_name, visit.clone(), quote!()))),
+			_ => (destructure, None),
+		};
+	}
+
+	let (enter, exit) = match fields {
+		syn::Fields::Unit => return (destructure, Some(quote!(fmt_derive_visitor.visit_unit(#name);))),
+		syn::Fields::Unnamed(_) => (quote!(enter_tuple), quote!(exit_tuple)),
+		syn::Fields::Named(_) => (quote!(enter_struct), quote!(exit_struct)),
+	};
+	let mut implementation = quote!(fmt_derive_visitor.#enter(#name););
+	for (label, config, var_name, visit) in &visits {
+		let visit = quote!(
+			fmt_derive_visitor.enter_field(#label);
+			#visit
+			fmt_derive_visitor.exit_field();
+		);
+		implementation.extend(skip_if(config, var_name, visit, quote!()));
+	}
+	implementation.extend(quote!(fmt_derive_visitor.#exit();));
+	(destructure, Some(implementation))
+}
+
+/// Reports the value of a single field, or returns `None` if it is ignored. A field that is printed by custom code is
+/// visited with the output of that code, while all other fields are inspected if their type implements `Inspect`,
+/// visited with their `Debug` output if it implements `Debug` and reported as their placeholder otherwise.
+fn visit_field(output: &FieldOutput) -> Option<proc_macro2::TokenStream> {
+	match output {
+		FieldOutput::Ignored => None,
+		FieldOutput::Debug(expr) => Some(quote!(fmt_derive_visitor.visit_debug(#expr);)),
+		FieldOutput::OrReplacement {
+			var_name,
+			ty,
+			opaque,
+			label,
+			strict,
+		} => {
+			let check = strict.check(label, ty, "Debug", &quote!(::core::fmt::Debug));
+			Some(quote!(
+				#check
+				_rt::InspectOrReplacement::<#ty>(#var_name).inspect(
+					|fmt_derive_visitor| _rt::DebugOrReplacement::<#ty>(#var_name).inspect_debug(#opaque, fmt_derive_visitor),
+					fmt_derive_visitor,
+				);
+			))
+		}
+	}
+}
+
+/// Reports the output of a format string that is given for a whole `struct`, variant or `enum`.
+fn visit_format(format: &Format, fields: &syn::Fields) -> proc_macro2::TokenStream {
+	let format = format.expand(fields);
+	quote!(fmt_derive_visitor.visit_debug(&_rt::DebugDisplay(&::core::format_args!(#format)));)
+}
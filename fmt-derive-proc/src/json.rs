@@ -0,0 +1,265 @@
+use proc_macro_error2::abort_if_dirty;
+use quote::quote;
+use syn::parse_macro_input;
+
+use crate::derive::{
+	FieldOutput, field_configs, field_defaults, field_var_name, reject_unsafe_keys, skip_if, transparent_error,
+};
+use crate::generics::{Bounds, GenericVariants};
+use crate::syntax::field_attribute::FieldAttribute;
+use crate::syntax::format::Format;
+use crate::syntax::{item_attribute, parse_attributes, variant_attribute};
+use crate::{manifest, rename};
+
+/// The attributes that configure the derived implementation of `JsonDisplay`, in ascending order of precedence. It
+/// renders the same fields that `Debug` prints, so the attributes of `Debug` apply as well.
+pub const ATTRIBUTES: &[&str] = &["fmt", "debug", "json"];
+
+pub fn json(item: proc_macro::TokenStream, crate_path: &proc_macro2::TokenStream) -> proc_macro::TokenStream {
+	let item = parse_macro_input!(item as syn::DeriveInput);
+	let item_name = &item.ident;
+
+	let item_config: item_attribute::ItemAttribute = parse_attributes(&item.attrs, ATTRIBUTES);
+	reject_unsafe_keys(&item_config, "Json");
+	let field_defaults = field_defaults(&item_config);
+	abort_if_dirty();
+
+	let mut bounds = Bounds::new(
+		&item.generics,
+		quote!(#crate_path::_rt::JsonDisplay),
+		item_config.bound.as_ref(),
+		item_config.infer_bounds,
+	);
+
+	let implementation = match &item_config.format {
+		Some(format) => {
+			let fields = match &item.data {
+				syn::Data::Struct(item_struct) => item_struct.fields.clone(),
+				syn::Data::Enum(_) | syn::Data::Union(_) => syn::Fields::Unit,
+			};
+			let destructure = match &fields {
+				syn::Fields::Unit => quote!(),
+				fields => {
+					let var_names = fields
+						.iter()
+						.enumerate()
+						.map(|(field_number, field)| field_var_name(field_number, field));
+					match fields {
+						syn::Fields::Named(_) => quote!(#[allow(unused_variables)] let Self { #(#var_names,)* } = self;),
+						_ => quote!(#[allow(unused_variables)] let Self(#(#var_names,)*) = self;),
+					}
+				}
+			};
+			let string = json_format(format, &fields);
+			quote!(#destructure #string)
+		}
+		None => match &item.data {
+			syn::Data::Struct(item_struct) => {
+				let name = rename::label(item_name, item_config.rename.as_ref(), None);
+				let configs = field_configs(&item_struct.fields, &field_defaults, ATTRIBUTES);
+				let (destructure, implementation) = process_fields(
+					&name,
+					&item_struct.fields,
+					&configs,
+					item_config.rename_all,
+					item_config.transparent,
+					&mut bounds,
+				);
+				if item_config.transparent && implementation.is_none() {
+					transparent_error(&[&item_config.keys], item_name.span(), &configs);
+				}
+				quote!(#[allow(unused_variables)] let Self #destructure = self; #implementation)
+			}
+			syn::Data::Enum(item_enum) => {
+				let mut stream = proc_macro2::TokenStream::new();
+				for variant in &item_enum.variants {
+					let variant_name = &variant.ident;
+					let variant_config: variant_attribute::VariantAttribute = parse_attributes(&variant.attrs, ATTRIBUTES);
+					let (destructure, implementation) = match &variant_config.format {
+						Some(format) => {
+							let var_names = variant
+								.fields
+								.iter()
+								.enumerate()
+								.map(|(field_number, field)| field_var_name(field_number, field));
+							let destructure = match &variant.fields {
+								syn::Fields::Unit => quote!(),
+								syn::Fields::Unnamed(_) => quote!((#(#var_names,)*)),
+								syn::Fields::Named(_) => quote!({#(#var_names,)*}),
+							};
+							(destructure, Some(json_format(format, &variant.fields)))
+						}
+						None => {
+							let name = rename::label(variant_name, variant_config.rename.as_ref(), item_config.rename_all);
+							let configs = field_configs(&variant.fields, &field_defaults, ATTRIBUTES);
+							let transparent = variant_config.transparent || item_config.transparent;
+							let (destructure, implementation) = process_fields(
+								&name,
+								&variant.fields,
+								&configs,
+								variant_config.rename_all.or(item_config.rename_all),
+								transparent,
+								&mut bounds,
+							);
+							if transparent && implementation.is_none() {
+								transparent_error(
+									&[&variant_config.keys, &item_config.keys],
+									variant_name.span(),
+									&configs,
+								);
+							}
+							// variants with fields are tagged by an object with their name as the only key
+							let implementation = match implementation {
+								Some(implementation) if !transparent && !matches!(variant.fields, syn::Fields::Unit) => Some(quote!(
+									_rt::JsonFields::object(fmt_derive_formatter_variable)
+										.field(#name, |fmt_derive_formatter_variable| { #implementation })
+										.finish()
+								)),
+								implementation => implementation,
+							};
+							(destructure, implementation)
+						}
+					};
+					stream.extend(quote!(#[allow(unused_variables)] Self::#variant_name #destructure => { #implementation }));
+				}
+				if item_enum.variants.is_empty() {
+					quote!(match *self {})
+				} else {
+					quote!(match self { #stream })
+				}
+			}
+			syn::Data::Union(_) => {
+				let name = format!("<{}>", item_name);
+				quote!(::core::write!(fmt_derive_formatter_variable, "{}", _rt::JsonString(#name)))
+			}
+		},
+	};
+
+	let GenericVariants {
+		params_bare: generics_params_bare,
+		params_no_defaults: generics_params_no_defaults,
+		where_clause: generics_where,
+	} = GenericVariants::new(&bounds.apply(&item.generics));
+	let track_manifest = manifest::track();
+	let result = quote!(
+		impl<#generics_params_no_defaults> #crate_path::_rt::JsonDisplay
+			for #item_name<#generics_params_bare> #generics_where
+		{
+			fn fmt_json(&self, fmt_derive_formatter_variable: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+				use #crate_path::_rt;
+				#track_manifest
+				use _rt::Replacement;
+				#implementation
+			}
+		}
+	);
+
+	abort_if_dirty();
+	result.into()
+}
+
+/// Renders the fields of a `struct` or variant named `name` as an object (named fields), an array (unnamed fields) or
+/// a string of the name (no fields). Returns the destructuring pattern and the implementation, which is `None` if the
+/// fields are `transparent` but there is not exactly one rendered field.
+fn process_fields(
+	name: &str,
+	fields: &syn::Fields,
+	configs: &[FieldAttribute],
+	rename_all: Option<rename::RenameRule>,
+	transparent: bool,
+	bounds: &mut Bounds,
+) -> (proc_macro2::TokenStream, Option<proc_macro2::TokenStream>) {
+	let mut destructure = proc_macro2::TokenStream::new();
+	let mut values = Vec::new();
+	for ((field_number, field), config) in fields.iter().enumerate().zip(configs) {
+		let var_name = field_var_name(field_number, field);
+		destructure.extend(quote!(#var_name,));
+
+		let output = FieldOutput::new(config, &var_name, field, fields);
+		bounds.add_field(field, config.bound.as_ref(), output.uses_trait());
+		if let Some(value) = json_field(&output) {
+			let label = field
+				.ident
+				.as_ref()
+				.map(|ident| rename::label(ident, config.rename.as_ref(), rename_all));
+			values.push((label, config, var_name, value));
+		}
+	}
+	let destructure = match fields {
+		syn::Fields::Unit => quote!(),
+		syn::Fields::Unnamed(_) => quote!((#destructure)),
+		syn::Fields::Named(_) => quote!({#destructure}),
+	};
+
+	if transparent {
+		return match values.as_slice() {
+			[(_, config, var_name, value)] => {
+				let null = quote!(fmt_derive_formatter_variable.write_str("null"));
+				(destructure, Some(skip_if(config, var_name, value.clone(), null)))
+			}
+			_ => (destructure, None),
+		};
+	}
+
+	let open = match fields {
+		syn::Fields::Unit => {
+			let implementation = quote!(::core::write!(fmt_derive_formatter_variable, "{}", _rt::JsonString(#name)));
+			return (destructure, Some(implementation));
+		}
+		syn::Fields::Unnamed(_) => quote!(array),
+		syn::Fields::Named(_) => quote!(object),
+	};
+	let mut implementation = quote!(let mut fmt_derive_json = _rt::JsonFields::#open(fmt_derive_formatter_variable););
+	for (label, config, var_name, value) in &values {
+		let add = match label {
+			Some(label) => quote!(fmt_derive_json.field(#label, |fmt_derive_formatter_variable| #value);),
+			None => quote!(fmt_derive_json.element(|fmt_derive_formatter_variable| #value);),
+		};
+		implementation.extend(skip_if(config, var_name, add, quote!()));
+	}
+	implementation.extend(quote!(fmt_derive_json.finish()));
+	(destructure, Some(implementation))
+}
+
+/// Renders a single field, or returns `None` if it is ignored. A field that is printed by custom code is rendered as a
+/// string of the output of that code, while all other fields are rendered as JSON if their type implements
+/// `JsonDisplay`, as a string of their `Debug` output if it implements `Debug` and as a string of their placeholder
+/// otherwise.
+fn json_field(output: &FieldOutput) -> Option<proc_macro2::TokenStream> {
+	match output {
+		FieldOutput::Ignored => None,
+		FieldOutput::Debug(expr) => Some(quote!(::core::write!(
+			fmt_derive_formatter_variable,
+			"{}",
+			_rt::JsonString(&::core::format_args!("{:?}", #expr))
+		))),
+		FieldOutput::OrReplacement {
+			var_name,
+			ty,
+			opaque,
+			label,
+			strict,
+		} => {
+			let check = strict.check(label, ty, "Debug", &quote!(::core::fmt::Debug));
+			Some(quote!({
+				#check
+				_rt::JsonOrReplacement::<#ty>(#var_name).fmt_json(
+					|fmt_derive_formatter_variable| {
+						_rt::DebugOrReplacement::<#ty>(#var_name).fmt_json_debug(#opaque, fmt_derive_formatter_variable)
+					},
+					fmt_derive_formatter_variable,
+				)
+			}))
+		}
+	}
+}
+
+/// Renders the output of a format string that is given for a whole `struct`, variant or `enum` as a string.
+fn json_format(format: &Format, fields: &syn::Fields) -> proc_macro2::TokenStream {
+	let format = format.expand(fields);
+	quote!(::core::write!(
+		fmt_derive_formatter_variable,
+		"{}",
+		_rt::JsonString(&::core::format_args!(#format))
+	))
+}
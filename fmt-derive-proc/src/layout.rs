@@ -0,0 +1,540 @@
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+use proc_macro_error2::{abort_if_dirty, emit_error};
+use quote::quote;
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+use crate::derive::{field_configs, field_defaults, field_var_name, reject_unsafe_keys, transparent_error};
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+use crate::generics::{Bounds, GenericVariants};
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+use crate::syntax::{
+	field_attribute::FieldAttribute, format::Format, item_attribute::ItemAttribute, parse_attributes,
+	placeholder::Placeholder, redact::Redact, variant_attribute::VariantAttribute,
+};
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+use crate::{manifest, rename};
+
+/// How the fields of a `struct` or variant are laid out, as given by `layout = "..."`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+	/// `Name { a: 1, b: 2 }` and `Name(1, 2)`, using the builders of `core::fmt::Formatter`.
+	#[default]
+	Debug,
+	/// `a=1 b=2`
+	Fields,
+	/// `1, 2`
+	Values,
+	/// `a=1 b="two words"`
+	Logfmt,
+}
+
+const LAYOUTS: &[(&str, Layout)] = &[
+	("debug", Layout::Debug),
+	("fields", Layout::Fields),
+	("values", Layout::Values),
+	("logfmt", Layout::Logfmt),
+];
+
+impl Layout {
+	/// Expands to the builder for the fields of a `struct` named `name`.
+	pub fn struct_builder(self, name: &str) -> proc_macro2::TokenStream {
+		match self.runtime() {
+			None => quote!(fmt_derive_formatter_variable.debug_struct(#name)),
+			Some(layout) => quote!(_rt::StructLayout::new(fmt_derive_formatter_variable, #layout)),
+		}
+	}
+
+	/// Expands to the builder for the fields of a tuple named `name`.
+	pub fn tuple_builder(self, name: &str) -> proc_macro2::TokenStream {
+		match self.runtime() {
+			None => quote!(fmt_derive_formatter_variable.debug_tuple(#name)),
+			Some(layout) => quote!(_rt::TupleLayout::new(fmt_derive_formatter_variable, #layout)),
+		}
+	}
+
+	/// The corresponding `_rt::Layout`, if the layout is not implemented by `core::fmt::Formatter` itself.
+	fn runtime(self) -> Option<proc_macro2::TokenStream> {
+		match self {
+			Layout::Debug => None,
+			Layout::Fields => Some(quote!(_rt::Layout::Fields)),
+			Layout::Values => Some(quote!(_rt::Layout::Values)),
+			Layout::Logfmt => Some(quote!(_rt::Layout::Logfmt)),
+		}
+	}
+}
+
+/// How the fields of a `struct` or variant are laid out around their values by derives that write their output as
+/// plain text instead of using the builders of `core::fmt::Formatter`.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+pub struct Shape<'a> {
+	pub name: &'a str,
+	pub layout: Layout,
+	pub non_exhaustive: bool,
+	pub rename_all: Option<crate::rename::RenameRule>,
+}
+
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+impl Shape<'_> {
+	/// The texts that are written before the first field, between two fields, after the last field and instead of all
+	/// of them if no field is printed, in this order.
+	pub fn frame(&self, named: bool) -> [String; 4] {
+		let name = self.name;
+		let rest = if self.non_exhaustive { ".." } else { "" };
+		match (self.layout, named) {
+			(Layout::Debug, true) => [
+				format!("{} {{ ", name),
+				", ".to_owned(),
+				if self.non_exhaustive { ", .. }" } else { " }" }.to_owned(),
+				if self.non_exhaustive {
+					format!("{} {{ .. }}", name)
+				} else {
+					name.to_owned()
+				},
+			],
+			(Layout::Debug, false) => [
+				format!("{}(", name),
+				", ".to_owned(),
+				if self.non_exhaustive { ", ..)" } else { ")" }.to_owned(),
+				if self.non_exhaustive {
+					format!("{}(..)", name)
+				} else {
+					name.to_owned()
+				},
+			],
+			(Layout::Values, _) => [
+				String::new(),
+				", ".to_owned(),
+				if self.non_exhaustive { ", .." } else { "" }.to_owned(),
+				rest.to_owned(),
+			],
+			(Layout::Fields | Layout::Logfmt, _) => [
+				String::new(),
+				" ".to_owned(),
+				if self.non_exhaustive { " .." } else { "" }.to_owned(),
+				rest.to_owned(),
+			],
+		}
+	}
+
+	/// The text that is written before the value of the field `label`.
+	pub fn label(&self, label: &str) -> String {
+		match self.layout {
+			Layout::Debug => format!("{}: ", label),
+			Layout::Fields | Layout::Logfmt => format!("{}=", label),
+			Layout::Values => String::new(),
+		}
+	}
+}
+
+/// A derive that writes its output piece by piece as plain text instead of using the builders of
+/// `core::fmt::Formatter`, i.e., the derives for `defmt` and `ufmt`. [`derive_text`] walks the item and lays out its
+/// fields, while the implementations of this trait only decide how a single piece of the output is written.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+pub trait TextDerive {
+	/// How the value of a single field is written.
+	type Field;
+
+	/// The name of the crate that the derive is for, e.g., `defmt`, as used in errors.
+	fn name(&self) -> &'static str;
+
+	/// The attributes that configure the derive, in ascending order of precedence.
+	fn attributes(&self) -> &'static [&'static str];
+
+	/// The name of the derived trait, e.g., `Format`.
+	fn trait_name(&self) -> &'static str;
+
+	/// The path of the derived trait, e.g., `defmt::Format`.
+	fn trait_path(&self) -> proc_macro2::TokenStream;
+
+	/// The signature of the method of the derived trait, whose formatter is called `fmt_derive_formatter_variable`.
+	fn signature(&self) -> proc_macro2::TokenStream;
+
+	/// A field that is printed as the fixed `text`, which is not escaped yet.
+	fn text(&self, text: &str) -> Self::Field;
+
+	/// A field that is printed by `format`, which may refer to the other fields in `scope`.
+	fn format(&self, format: &Format, scope: &syn::Fields) -> syn::Result<Self::Field>;
+
+	/// A field `var_name` of type `ty` that is printed by the derived trait if possible and as `placeholder` otherwise.
+	fn placeholder(&self, placeholder: &Placeholder, ty: &syn::Type, var_name: &syn::Ident) -> syn::Result<Self::Field>;
+
+	/// Writes `field`, preceded by `prefix`, which is not escaped yet.
+	fn write_field(&self, prefix: &str, field: &Self::Field) -> proc_macro2::TokenStream;
+
+	/// Writes `field` as the whole output of a `transparent` item, so that it is formatted exactly like the field itself.
+	fn forward(&self, field: &Self::Field) -> proc_macro2::TokenStream {
+		self.write_field("", field)
+	}
+
+	/// Writes `text` as is, unless it is empty.
+	fn write_str(&self, text: &str) -> proc_macro2::TokenStream;
+
+	/// Writes `fields`, each preceded by its prefix, followed by `close`.
+	fn write_fields(&self, fields: &[(String, &Self::Field)], close: &str) -> proc_macro2::TokenStream {
+		let mut output = proc_macro2::TokenStream::new();
+		for (prefix, field) in fields {
+			output.extend(self.write_field(prefix, field));
+		}
+		output.extend(self.write_str(close));
+		output
+	}
+
+	/// Turns the statements that write the output into the body of the method (or of one of its `match` arms).
+	fn finish(&self, statements: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		statements
+	}
+}
+
+/// The output of a single printed field.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+struct Entry<F> {
+	field: F,
+	/// Statements that must precede the output, e.g., the checks of `strict`.
+	checks: proc_macro2::TokenStream,
+	/// The condition under which the field is skipped, as given by `skip_if`.
+	skip_if: Option<proc_macro2::TokenStream>,
+}
+
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+impl<F> Entry<F> {
+	/// Guards `write` by the condition of `skip_if`, if any.
+	fn guard(&self, write: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		let checks = &self.checks;
+		match &self.skip_if {
+			Some(condition) => quote!(#checks if !(#condition) { #write }),
+			None => quote!(#checks #write),
+		}
+	}
+}
+
+/// Derives the trait of `derive` for `item`.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+pub fn derive_text<D: TextDerive>(
+	item: proc_macro::TokenStream,
+	use_rt: &proc_macro2::TokenStream,
+	derive: &D,
+) -> proc_macro::TokenStream {
+	let item = syn::parse_macro_input!(item as syn::DeriveInput);
+	let item_name = &item.ident;
+	let attributes = derive.attributes();
+
+	let item_config: ItemAttribute = parse_attributes(&item.attrs, attributes);
+	reject_unsafe_keys(&item_config, derive.name());
+	if item_config.layout == Some(Layout::Logfmt) {
+		let key = item_config.keys.iter().find(|key| *key == "layout");
+		emit_error!(
+			key.map_or(item_name.span(), |key| key.span()), "the layout `logfmt` cannot be used with `{}`", derive.name();
+			help = "`{}` cannot quote values at runtime, use `layout = \"fields\"` instead", derive.name()
+		);
+	}
+	let field_defaults = field_defaults(&item_config);
+	abort_if_dirty();
+
+	let layout = item_config.layout.unwrap_or_default();
+	let non_exhaustive = item_config.non_exhaustive.unwrap_or_default();
+	let mut bounds = Bounds::new(
+		&item.generics,
+		derive.trait_path(),
+		item_config.bound.as_ref(),
+		item_config.infer_bounds,
+	);
+
+	let implementation = match &item_config.format {
+		Some(format) => {
+			let fields = match &item.data {
+				syn::Data::Struct(item_struct) => item_struct.fields.clone(),
+				syn::Data::Enum(_) | syn::Data::Union(_) => syn::Fields::Unit,
+			};
+			let destructure = match &fields {
+				syn::Fields::Unit => quote!(),
+				fields => {
+					let var_names = fields
+						.iter()
+						.enumerate()
+						.map(|(field_number, field)| field_var_name(field_number, field));
+					match fields {
+						syn::Fields::Named(_) => quote!(#[allow(unused_variables)] let Self { #(#var_names,)* } = self;),
+						_ => quote!(#[allow(unused_variables)] let Self(#(#var_names,)*) = self;),
+					}
+				}
+			};
+			let write = derive.finish(write_format(derive, format, &fields));
+			quote!(#destructure #write)
+		}
+		None => match &item.data {
+			syn::Data::Struct(item_struct) => {
+				let name = rename::label(item_name, item_config.rename.as_ref(), None);
+				let configs = field_configs(&item_struct.fields, &field_defaults, attributes);
+				let shape = Shape {
+					name: &name,
+					layout,
+					non_exhaustive: non_exhaustive.applies(&item.attrs, &configs),
+					rename_all: item_config.rename_all,
+				};
+				let (destructure, implementation) = process_fields(
+					derive,
+					&item_struct.fields,
+					&configs,
+					&shape,
+					item_config.transparent,
+					&mut bounds,
+				);
+				if item_config.transparent && implementation.is_none() {
+					transparent_error(&[&item_config.keys], item_name.span(), &configs);
+				}
+				quote!(#[allow(unused_variables)] let Self #destructure = self; #implementation)
+			}
+			syn::Data::Enum(item_enum) => {
+				if item_enum.variants.is_empty() {
+					quote!(::core::unreachable!())
+				} else {
+					let mut stream = proc_macro2::TokenStream::new();
+					for variant in &item_enum.variants {
+						let variant_name = &variant.ident;
+						let variant_config: VariantAttribute = parse_attributes(&variant.attrs, attributes);
+						let (destructure, implementation) = match &variant_config.format {
+							Some(format) => {
+								let var_names = variant
+									.fields
+									.iter()
+									.enumerate()
+									.map(|(field_number, field)| field_var_name(field_number, field));
+								let destructure = match &variant.fields {
+									syn::Fields::Unit => quote!(),
+									syn::Fields::Unnamed(_) => quote!((#(#var_names,)*)),
+									syn::Fields::Named(_) => quote!({#(#var_names,)*}),
+								};
+								let write = derive.finish(write_format(derive, format, &variant.fields));
+								(destructure, Some(write))
+							}
+							None => {
+								let name = rename::label(variant_name, variant_config.rename.as_ref(), item_config.rename_all);
+								let configs = field_configs(&variant.fields, &field_defaults, attributes);
+								let shape = Shape {
+									name: &name,
+									layout,
+									non_exhaustive: non_exhaustive.applies(&variant.attrs, &configs),
+									rename_all: variant_config.rename_all.or(item_config.rename_all),
+								};
+								let transparent = variant_config.transparent || item_config.transparent;
+								let (destructure, implementation) =
+									process_fields(derive, &variant.fields, &configs, &shape, transparent, &mut bounds);
+								if transparent && implementation.is_none() {
+									transparent_error(
+										&[&variant_config.keys, &item_config.keys],
+										variant_name.span(),
+										&configs,
+									);
+								}
+								(destructure, implementation)
+							}
+						};
+						stream.extend(quote!(#[allow(unused_variables)] Self::#variant_name #destructure => { #implementation }));
+					}
+					quote!(match self { #stream })
+				}
+			}
+			syn::Data::Union(_) => derive.finish(derive.write_str(&format!("<{}>", item_name))),
+		},
+	};
+
+	let trait_path = derive.trait_path();
+	let signature = derive.signature();
+	let GenericVariants {
+		params_bare: generics_params_bare,
+		params_no_defaults: generics_params_no_defaults,
+		where_clause: generics_where,
+	} = GenericVariants::new(&bounds.apply(&item.generics));
+	let track_manifest = manifest::track();
+	let result = quote!(
+		impl<#generics_params_no_defaults> #trait_path for #item_name<#generics_params_bare> #generics_where {
+			#signature {
+				#use_rt
+				#track_manifest
+				use _rt::Replacement;
+				#implementation
+			}
+		}
+	);
+
+	abort_if_dirty();
+	result.into()
+}
+
+/// Prints the fields of a `struct` or variant. Returns the destructuring pattern and the implementation, which is
+/// `None` if the fields are `transparent` but there is not exactly one printed field.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+fn process_fields<D: TextDerive>(
+	derive: &D,
+	fields: &syn::Fields,
+	configs: &[FieldAttribute],
+	shape: &Shape,
+	transparent: bool,
+	bounds: &mut Bounds,
+) -> (proc_macro2::TokenStream, Option<proc_macro2::TokenStream>) {
+	let mut destructure = proc_macro2::TokenStream::new();
+	let mut entries = Vec::new();
+	for ((field_number, field), config) in fields.iter().enumerate().zip(configs) {
+		let var_name = field_var_name(field_number, field);
+		destructure.extend(quote!(#var_name,));
+		if let Some(entry) = field_entry(derive, config, &var_name, field, fields, bounds) {
+			let label = match &field.ident {
+				Some(ident) => rename::label(ident, config.rename.as_ref(), shape.rename_all),
+				None => entries.len().to_string(),
+			};
+			entries.push((label, entry));
+		}
+	}
+	let destructure = match fields {
+		syn::Fields::Unit => quote!(),
+		syn::Fields::Unnamed(_) => quote!((#destructure)),
+		syn::Fields::Named(_) => quote!({#destructure}),
+	};
+
+	if transparent {
+		return match entries.as_slice() {
+			[(_, entry)] => (
+				destructure,
+				Some(derive.finish(entry.guard(derive.forward(&entry.field)))),
+			),
+			_ => (destructure, None),
+		};
+	}
+
+	if let syn::Fields::Unit = fields {
+		return (destructure, Some(derive.finish(derive.write_str(shape.name))));
+	}
+
+	let named = matches!(fields, syn::Fields::Named(_));
+	let [open, separator, close, empty] = shape.frame(named);
+	let prefix = |label: &str| {
+		if named || shape.layout != Layout::Debug {
+			shape.label(label)
+		} else {
+			String::new()
+		}
+	};
+
+	let mut implementation = proc_macro2::TokenStream::new();
+	if entries.is_empty() {
+		implementation.extend(derive.write_str(&empty));
+	} else if entries.iter().all(|(_, entry)| entry.skip_if.is_none()) {
+		// all fields are printed unconditionally, so the separators are known in advance
+		let mut fields = Vec::new();
+		for (index, (label, entry)) in entries.iter().enumerate() {
+			let before = if index == 0 { &open } else { &separator };
+			fields.push((format!("{}{}", before, prefix(label)), &entry.field));
+			implementation.extend(entry.checks.clone());
+		}
+		implementation.extend(derive.write_fields(&fields, &close));
+	} else {
+		// the separators depend on which fields are skipped at runtime
+		implementation.extend(quote!(let mut fmt_derive_printed = false;));
+		for (label, entry) in &entries {
+			let first = derive.write_field(&format!("{}{}", open, prefix(label)), &entry.field);
+			let next = derive.write_field(&format!("{}{}", separator, prefix(label)), &entry.field);
+			implementation.extend(entry.guard(quote!(
+				if fmt_derive_printed { #next } else { #first }
+				fmt_derive_printed = true;
+			)));
+		}
+		let (close, empty) = (derive.write_str(&close), derive.write_str(&empty));
+		implementation.extend(quote!(if fmt_derive_printed { #close } else { #empty }));
+	}
+	(destructure, Some(derive.finish(implementation)))
+}
+
+/// Translates how a single field is printed, or returns `None` if it is ignored.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+fn field_entry<D: TextDerive>(
+	derive: &D,
+	config: &FieldAttribute,
+	var_name: &syn::Ident,
+	field: &syn::Field,
+	scope: &syn::Fields,
+	bounds: &mut Bounds,
+) -> Option<Entry<D::Field>> {
+	let skip_if = config.skip_if.as_ref().map(|predicate| quote!(#predicate(#var_name)));
+	let mut uses_trait = false;
+	let mut checks = quote!();
+	let field_output = if config.ignore {
+		None
+	} else if let Some(redact) = &config.redact {
+		match redact {
+			Redact::Fixed => Some(Ok(derive.text("***"))),
+			_ => {
+				let key = config.keys.iter().find(|key| *key == "redact");
+				emit_error!(
+					key.map_or(var_name.span(), |key| key.span()),
+					"only `redact` without a strategy can be used with `{}`", derive.name();
+					help = "`{}` cannot inspect the output of `Display` at runtime", derive.name()
+				);
+				None
+			}
+		}
+	} else if let Some(with) = &config.with {
+		emit_error!(
+			with, "`with` cannot be used with `{}`, as it requires a `core::fmt::Formatter`", derive.name();
+			help = "give the field a format string or `ignore` it with `#[{}(...)]`", derive.attributes()[2]
+		);
+		None
+	} else if let Some(format) = &config.format {
+		Some(derive.format(format, scope))
+	} else {
+		uses_trait = true;
+		let ty = &field.ty;
+		let label = match &field.ident {
+			Some(ident) => ident.unraw().to_string(),
+			None => var_name.to_string().trim_start_matches('_').to_owned(),
+		};
+		checks = config
+			.strict
+			.unwrap_or_default()
+			.check(&label, ty, derive.trait_name(), &derive.trait_path());
+		Some(derive.placeholder(&config.placeholder.clone().unwrap_or_default(), ty, var_name))
+	};
+	bounds.add_field(field, config.bound.as_ref(), uses_trait);
+	match field_output? {
+		Ok(field) => Some(Entry { field, checks, skip_if }),
+		Err(error) => {
+			emit_error!(error);
+			None
+		}
+	}
+}
+
+/// Writes the output of a format string that is given for a whole `struct`, variant or `enum`.
+#[cfg(any(feature = "defmt", feature = "ufmt"))]
+fn write_format<D: TextDerive>(derive: &D, format: &Format, fields: &syn::Fields) -> proc_macro2::TokenStream {
+	match derive.format(format, fields) {
+		Ok(field) => derive.write_field("", &field),
+		Err(error) => {
+			emit_error!(error);
+			quote!()
+		}
+	}
+}
+
+impl Parse for Layout {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let value = literal.value();
+		match LAYOUTS.iter().find(|(name, _)| *name == value) {
+			Some((_, layout)) => Ok(*layout),
+			None => {
+				let expected = LAYOUTS
+					.iter()
+					.map(|(name, _)| format!("`{}`", name))
+					.collect::<Vec<_>>()
+					.join(", ");
+				Err(syn::Error::new(
+					literal.span(),
+					format!("unknown layout `{}`, expected one of {}", value, expected),
+				))
+			}
+		}
+	}
+}
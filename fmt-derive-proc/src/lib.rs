@@ -1,33 +1,144 @@
 use proc_macro_error2::proc_macro_error;
 use quote::quote;
 
-mod debug;
-mod display;
+#[cfg(feature = "defmt")]
+mod defmt;
+mod derive;
+mod error;
+mod fmt_trait;
+mod from_str;
 mod generics;
+mod inspect;
+mod json;
+mod layout;
+mod manifest;
+mod non_exhaustive;
+mod rename;
+mod strict;
 mod syntax;
+#[cfg(feature = "ufmt")]
+mod ufmt;
+
+use fmt_trait::FmtTrait;
 
 #[proc_macro_error]
 #[proc_macro_derive(Debug, attributes(fmt, debug))]
 pub fn debug(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-	debug::debug(item, &use_rt())
+	derive::derive(item, &use_rt(), &FmtTrait::DEBUG)
+}
+
+#[cfg(feature = "defmt")]
+#[proc_macro_error]
+#[proc_macro_derive(Defmt, attributes(fmt, debug, defmt))]
+pub fn defmt(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	defmt::defmt(item, &use_rt())
 }
 
 #[proc_macro_error]
 #[proc_macro_derive(Display, attributes(fmt, display))]
 pub fn display(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-	display::display(item, &use_rt())
+	derive::derive(item, &use_rt(), &FmtTrait::DISPLAY)
 }
 
 #[proc_macro_error]
 #[proc_macro_derive(Fmt, attributes(fmt, debug, display))]
 pub fn fmt(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let use_rt = use_rt();
-	let mut stream = debug::debug(item.clone(), &use_rt);
-	stream.extend(display::display(item, &use_rt));
+	let mut stream = derive::derive(item.clone(), &use_rt, &FmtTrait::DEBUG);
+	stream.extend(derive::derive(item, &use_rt, &FmtTrait::DISPLAY));
+	stream
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Error, attributes(fmt, display, error))]
+pub fn error(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let use_rt = use_rt();
+	let mut stream = derive::derive(item.clone(), &use_rt, &FmtTrait::DISPLAY);
+	stream.extend(error::error(item, &use_rt));
 	stream
 }
 
+#[proc_macro_error]
+#[proc_macro_derive(FromStr, attributes(fmt, display))]
+pub fn from_str(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	from_str::from_str(item, &crate_path())
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Inspect, attributes(fmt, debug, inspect))]
+pub fn inspect(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	inspect::inspect(item, &crate_path())
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Json, attributes(fmt, debug, json))]
+pub fn json(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	json::json(item, &crate_path())
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(LowerHex, attributes(lower_hex))]
+pub fn lower_hex(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::LOWER_HEX)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(UpperHex, attributes(upper_hex))]
+pub fn upper_hex(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::UPPER_HEX)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Binary, attributes(binary))]
+pub fn binary(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::BINARY)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Octal, attributes(octal))]
+pub fn octal(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::OCTAL)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(LowerExp, attributes(lower_exp))]
+pub fn lower_exp(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::LOWER_EXP)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(UpperExp, attributes(upper_exp))]
+pub fn upper_exp(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::UPPER_EXP)
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(Pointer, attributes(pointer))]
+pub fn pointer(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	derive::derive(item, &use_rt(), &FmtTrait::POINTER)
+}
+
+#[cfg(feature = "ufmt")]
+#[proc_macro_error]
+#[proc_macro_derive(UDebug, attributes(fmt, debug, udebug))]
+pub fn udebug(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	ufmt::ufmt(item, &use_rt(), &ufmt::UfmtTrait::UDEBUG)
+}
+
+#[cfg(feature = "ufmt")]
+#[proc_macro_error]
+#[proc_macro_derive(UDisplay, attributes(fmt, display, udisplay))]
+pub fn udisplay(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	ufmt::ufmt(item, &use_rt(), &ufmt::UfmtTrait::UDISPLAY)
+}
+
 fn use_rt() -> proc_macro2::TokenStream {
+	let crate_path = crate_path();
+	quote!(use #crate_path::_rt;)
+}
+
+/// The path to the `fmt-derive` crate, as seen from the crate that uses the derive macros.
+fn crate_path() -> proc_macro2::TokenStream {
 	match proc_macro_crate::crate_name("fmt-derive").unwrap_or_else(|err| {
 		proc_macro_error2::abort_call_site!(
 			"{}", err;
@@ -35,12 +146,10 @@ fn use_rt() -> proc_macro2::TokenStream {
 			note = "Did you accidentally import `fmt-derive-proc` instead?"
 		)
 	}) {
-		proc_macro_crate::FoundCrate::Itself => quote!(
-			use crate::_rt;
-		),
+		proc_macro_crate::FoundCrate::Itself => quote!(crate),
 		proc_macro_crate::FoundCrate::Name(name) => {
 			let ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
-			quote!( use ::#ident::_rt; )
+			quote!(::#ident)
 		}
 	}
 }
@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use quote::quote;
+
+/// The table in the manifest of the crate that uses the derives that holds the defaults for all of its items.
+const METADATA_TABLE: &str = "fmt-derive";
+
+/// The `[package.metadata.fmt-derive]` table of a manifest, as read when the manifest was last modified at `modified`.
+struct Cached {
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	table: Option<toml_edit::Item>,
+}
+
+thread_local! {
+	/// The manifest is read once per compilation instead of once per derive, but read again if it changes while the
+	/// process lives on (e.g., in an IDE).
+	static CACHE: RefCell<Option<Cached>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` with the `[package.metadata.fmt-derive]` table of the manifest of the crate that uses the derives, if
+/// there is one.
+fn with_table<R>(f: impl FnOnce(Option<&toml_edit::Item>) -> R) -> R {
+	let Some(path) = path() else {
+		return f(None);
+	};
+	let modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+	CACHE.with_borrow_mut(|cache| {
+		let cached = match cache {
+			Some(cached) if cached.path == path && cached.modified == modified && modified.is_some() => cached,
+			_ => cache.insert(Cached {
+				table: read_table(&path),
+				path,
+				modified,
+			}),
+		};
+		f(cached.table.as_ref())
+	})
+}
+
+fn read_table(path: &std::path::Path) -> Option<toml_edit::Item> {
+	let manifest = std::fs::read_to_string(path).ok()?;
+	// an invalid manifest is reported by Cargo itself
+	let mut manifest = manifest.parse::<toml_edit::DocumentMut>().ok()?;
+	manifest
+		.get_mut("package")?
+		.get_mut("metadata")?
+		.get_mut(METADATA_TABLE)
+		.map(std::mem::take)
+}
+
+/// The value of `key` in the `[package.metadata.fmt-derive]` table of the manifest of the crate that uses the derives,
+/// as parsed from a string by `parse`, or `None` if it is not set.
+pub fn metadata<T>(key: &str, parse: impl FnOnce(&str) -> syn::Result<T>) -> syn::Result<Option<T>> {
+	let error = |message: &dyn std::fmt::Display| {
+		syn::Error::new(
+			proc_macro2::Span::call_site(),
+			format!("invalid `package.metadata.{}.{}`: {}", METADATA_TABLE, key, message),
+		)
+	};
+	let value = with_table(|table| match table.and_then(|table| table.get(key)) {
+		Some(value) => value
+			.as_str()
+			.map(|value| Some(value.to_owned()))
+			.ok_or_else(|| error(&"expected a string")),
+		None => Ok(None),
+	})?;
+	match value {
+		Some(value) => parse(&value).map(Some).map_err(|parse_error| error(&parse_error)),
+		None => Ok(None),
+	}
+}
+
+/// Expands to an item that makes the output of a derive depend on the manifest read by [`metadata`], as Cargo does not
+/// rebuild a crate when only its metadata changes. Crates without a `[package.metadata.fmt-derive]` table do not depend
+/// on their manifest, so that adding the table requires a rebuild (e.g., by touching a source file).
+pub fn track() -> proc_macro2::TokenStream {
+	if !with_table(|table| table.is_some()) {
+		return quote!();
+	}
+	match path().and_then(|path| path.to_str().map(str::to_owned)) {
+		Some(path) => quote!(
+			const _: &[u8] = ::core::include_bytes!(#path);
+		),
+		None => quote!(),
+	}
+}
+
+/// The path to the manifest of the crate that uses the derives, if it is built by Cargo.
+fn path() -> Option<PathBuf> {
+	let dir = std::env::var_os("CARGO_MANIFEST_DIR")?;
+	Some(std::path::Path::new(&dir).join("Cargo.toml"))
+}
@@ -0,0 +1,57 @@
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+
+use crate::syntax::field_attribute::FieldAttribute;
+
+/// When the fields of a `struct` or variant end with `..`, as given by `non_exhaustive` or `non_exhaustive = "..."`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonExhaustive {
+	/// Never, like the derives of `std`.
+	#[default]
+	Never,
+	/// If any field is ignored or may be skipped by `skip_if`, or the `struct` or variant is marked `#[non_exhaustive]`.
+	Auto,
+	/// Always.
+	Always,
+}
+
+impl NonExhaustive {
+	/// Whether the fields of a `struct` or variant with the given attributes and fields end with `..`.
+	pub fn applies(self, attrs: &[syn::Attribute], configs: &[FieldAttribute]) -> bool {
+		match self {
+			NonExhaustive::Never => false,
+			NonExhaustive::Auto => {
+				configs.iter().any(|config| config.ignore || config.skip_if.is_some())
+					|| attrs.iter().any(|attr| attr.path().is_ident("non_exhaustive"))
+			}
+			NonExhaustive::Always => true,
+		}
+	}
+
+	/// Expands to the call that finishes the builder of a `struct` or variant with the given attributes and fields.
+	pub fn finish(self, attrs: &[syn::Attribute], configs: &[FieldAttribute]) -> proc_macro2::TokenStream {
+		if self.applies(attrs, configs) {
+			quote!(w.finish_non_exhaustive())
+		} else {
+			quote!(w.finish())
+		}
+	}
+}
+
+impl Parse for NonExhaustive {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		match literal.value().as_str() {
+			"never" => Ok(NonExhaustive::Never),
+			"auto" => Ok(NonExhaustive::Auto),
+			"always" => Ok(NonExhaustive::Always),
+			value => Err(syn::Error::new(
+				literal.span(),
+				format!(
+					"unknown non-exhaustive mode `{}`, expected `never`, `auto` or `always`",
+					value
+				),
+			)),
+		}
+	}
+}
@@ -0,0 +1,121 @@
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+
+/// Returns the name under which `ident` is printed: an explicit `rename` takes precedence over a `rename_all` rule,
+/// which in turn takes precedence over the identifier itself (without any `r#` prefix).
+pub fn label(ident: &syn::Ident, rename: Option<&syn::LitStr>, rule: Option<RenameRule>) -> String {
+	match (rename, rule) {
+		(Some(rename), _) => rename.value(),
+		(None, Some(rule)) => rule.apply(&ident.unraw().to_string()),
+		(None, None) => ident.unraw().to_string(),
+	}
+}
+
+/// A case conversion rule, as given by `rename_all = "..."`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenameRule {
+	Lower,
+	Upper,
+	Pascal,
+	Camel,
+	Snake,
+	ScreamingSnake,
+	Kebab,
+	ScreamingKebab,
+}
+
+const RULES: &[(&str, RenameRule)] = &[
+	("lowercase", RenameRule::Lower),
+	("UPPERCASE", RenameRule::Upper),
+	("PascalCase", RenameRule::Pascal),
+	("camelCase", RenameRule::Camel),
+	("snake_case", RenameRule::Snake),
+	("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+	("kebab-case", RenameRule::Kebab),
+	("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+];
+
+impl RenameRule {
+	/// Applies the rule to an identifier, which may be given in either `snake_case` (as is customary for fields) or
+	/// `PascalCase` (as is customary for variants).
+	pub fn apply(self, name: &str) -> String {
+		let words = words(name);
+		match self {
+			RenameRule::Lower => words.concat().to_lowercase(),
+			RenameRule::Upper => words.concat().to_uppercase(),
+			RenameRule::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+			RenameRule::Camel => words
+				.iter()
+				.enumerate()
+				.map(|(index, word)| {
+					if index == 0 {
+						word.to_lowercase()
+					} else {
+						capitalize(word)
+					}
+				})
+				.collect(),
+			RenameRule::Snake => words.join("_").to_lowercase(),
+			RenameRule::ScreamingSnake => words.join("_").to_uppercase(),
+			RenameRule::Kebab => words.join("-").to_lowercase(),
+			RenameRule::ScreamingKebab => words.join("-").to_uppercase(),
+		}
+	}
+}
+
+impl Parse for RenameRule {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let value = literal.value();
+		match RULES.iter().find(|(name, _)| *name == value) {
+			Some((_, rule)) => Ok(*rule),
+			None => {
+				let expected = RULES
+					.iter()
+					.map(|(name, _)| format!("`{}`", name))
+					.collect::<Vec<_>>()
+					.join(", ");
+				Err(syn::Error::new(
+					literal.span(),
+					format!("unknown rename rule `{}`, expected one of {}", value, expected),
+				))
+			}
+		}
+	}
+}
+
+/// Splits an identifier into its words at underscores, dashes and lower-to-upper case transitions. A run of upper
+/// case letters is kept together as an acronym, so that `HTTPServer` is split into `HTTP` and `Server`.
+fn words(name: &str) -> Vec<String> {
+	let mut words = Vec::new();
+	let mut current = String::new();
+	let chars = name.chars().collect::<Vec<_>>();
+	for (index, &c) in chars.iter().enumerate() {
+		if c == '_' || c == '-' {
+			if !current.is_empty() {
+				words.push(core::mem::take(&mut current));
+			}
+			continue;
+		}
+		if c.is_uppercase() && !current.is_empty() {
+			let previous = chars[index - 1];
+			let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+			if !previous.is_uppercase() || next_is_lower {
+				words.push(core::mem::take(&mut current));
+			}
+		}
+		current.push(c);
+	}
+	if !current.is_empty() {
+		words.push(current);
+	}
+	words
+}
+
+fn capitalize(word: &str) -> String {
+	let mut chars = word.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+		None => String::new(),
+	}
+}
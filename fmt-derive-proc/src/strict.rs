@@ -0,0 +1,94 @@
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+
+use crate::syntax::placeholder::type_string;
+
+/// What happens when a field falls back to its placeholder, as given by `strict` or `strict = "..."`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strict {
+	/// Nothing, the placeholder is printed silently.
+	#[default]
+	Allow,
+	/// A deprecation warning is emitted.
+	Warn,
+	/// Compilation fails.
+	Deny,
+}
+
+impl Strict {
+	/// The strictness given by `strict = "..."` in the `[package.metadata.fmt-derive]` table of the manifest of the
+	/// crate that uses the derives, or the default if it is not set.
+	pub fn from_manifest() -> syn::Result<Self> {
+		let strict = crate::manifest::metadata("strict", |value| {
+			Self::from_name(value).ok_or_else(|| syn::Error::new(proc_macro2::Span::call_site(), Self::unknown(value)))
+		})?;
+		Ok(strict.unwrap_or_default())
+	}
+
+	fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"allow" => Some(Strict::Allow),
+			"warn" => Some(Strict::Warn),
+			"deny" => Some(Strict::Deny),
+			_ => None,
+		}
+	}
+
+	fn unknown(name: &str) -> String {
+		format!("unknown strictness `{}`, expected `allow`, `warn` or `deny`", name)
+	}
+
+	/// Expands to a statement that, depending on the mode, does nothing, warns or fails compilation if the field
+	/// `label` of type `ty` does not implement the derived trait, which is called `trait_name` and found at `trait_path`.
+	pub fn check(
+		self,
+		label: &str,
+		ty: &syn::Type,
+		trait_name: &str,
+		trait_path: &proc_macro2::TokenStream,
+	) -> proc_macro2::TokenStream {
+		let message = format!(
+			"field `{}` of type `{}` does not implement `{}`",
+			label,
+			type_string(quote!(#ty)),
+			trait_name
+		);
+		match self {
+			Strict::Allow => quote!(),
+			Strict::Warn => {
+				let note = format!("{} and is printed as a placeholder", message);
+				quote_spanned! {ty.span()=> {
+					#[allow(dead_code)]
+					struct FmtDeriveStrict<T: ?Sized>(::core::marker::PhantomData<T>);
+					#[allow(dead_code)]
+					trait Fallback {
+						#[deprecated(note = #note)]
+						fn check(&self) {}
+					}
+					impl<T: ?Sized> Fallback for FmtDeriveStrict<T> {}
+					#[allow(dead_code)]
+					impl<T: ?Sized + #trait_path> FmtDeriveStrict<T> {
+						fn check(&self) {}
+					}
+					FmtDeriveStrict::<#ty>(::core::marker::PhantomData).check();
+				}}
+			}
+			Strict::Deny => quote_spanned! {ty.span()=> {
+				#[diagnostic::on_unimplemented(message = #message, label = "would be printed as a placeholder")]
+				trait FmtDeriveStrict {}
+				impl<T: ?Sized + #trait_path> FmtDeriveStrict for T {}
+				fn check<T: ?Sized + FmtDeriveStrict>() {}
+				check::<#ty>();
+			}},
+		}
+	}
+}
+
+impl Parse for Strict {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let value = literal.value();
+		Self::from_name(&value).ok_or_else(|| syn::Error::new(literal.span(), Self::unknown(&value)))
+	}
+}
@@ -0,0 +1,49 @@
+use syn::parse::{Parse, ParseStream};
+
+#[derive(Clone, Debug, Default)]
+pub struct ErrorAttribute {
+	pub keys: Vec<syn::Ident>,
+	pub source: bool,
+	pub from: bool,
+}
+
+impl super::KeyValueAttribute for ErrorAttribute {
+	const KEYS: &'static [&'static str] = &["from", "source"];
+	const EXCLUSIVE: &'static [&'static [&'static str]] = &[];
+
+	fn keys(&self) -> &[syn::Ident] {
+		&self.keys
+	}
+
+	fn update(&mut self, other: Self) {
+		self.keys.extend(other.keys);
+		if other.source {
+			self.source = true;
+		}
+		if other.from {
+			self.from = true;
+		}
+	}
+}
+
+impl Parse for ErrorAttribute {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut result = Self::default();
+
+		while !input.is_empty() {
+			let key = super::parse_key(input, <Self as super::KeyValueAttribute>::KEYS)?;
+			result.keys.push(key.clone());
+			match key.to_string().as_str() {
+				"from" => result.from = true,
+				"source" => result.source = true,
+				_ => unreachable!("`parse_key` only accepts the given keys"),
+			}
+
+			if !input.is_empty() {
+				let _comma: syn::Token![,] = input.parse()?;
+			}
+		}
+
+		Ok(result)
+	}
+}
@@ -1,39 +1,118 @@
 use syn::parse::{Parse, ParseStream};
 
+use super::placeholder::Placeholder;
+use super::redact::Redact;
+use crate::strict::Strict;
+
 #[derive(Clone, Debug, Default)]
 pub struct FieldAttribute {
+	pub keys: Vec<syn::Ident>,
+	pub bound: Option<super::Bound>,
 	pub ignore: bool,
-	pub format: Option<proc_macro2::TokenStream>,
+	pub placeholder: Option<Placeholder>,
+	pub rename: Option<syn::LitStr>,
+	pub redact: Option<Redact>,
+	pub skip_if: Option<syn::ExprPath>,
+	pub strict: Option<Strict>,
+	pub with: Option<syn::ExprPath>,
+	pub format: Option<super::format::Format>,
 }
 
 impl FieldAttribute {
-	pub fn update(&mut self, other: Self) {
-		if other.ignore {
-			self.ignore = true;
-		}
-		if other.format.is_some() {
+	/// Whether any of the keys that decide how the field is printed is given.
+	fn has_output(&self) -> bool {
+		self.ignore || self.redact.is_some() || self.with.is_some() || self.format.is_some()
+	}
+}
+
+impl super::KeyValueAttribute for FieldAttribute {
+	const KEYS: &'static [&'static str] = &[
+		"bound",
+		"format",
+		"ignore",
+		"placeholder",
+		"redact",
+		"rename",
+		"skip_if",
+		"strict",
+		"with",
+	];
+	const EXCLUSIVE: &'static [&'static [&'static str]] =
+		&[&["ignore", "redact", "with", "format"], &["ignore", "skip_if"]];
+
+	fn keys(&self) -> &[syn::Ident] {
+		&self.keys
+	}
+
+	fn update(&mut self, other: Self) {
+		// `ignore`, `redact`, `with` and the format are alternatives, so they are overridden together
+		if other.has_output() {
+			self.ignore = other.ignore;
+			self.redact = other.redact;
+			self.with = other.with;
 			self.format = other.format;
 		}
+		self.keys.extend(other.keys);
+		if other.bound.is_some() {
+			self.bound = other.bound;
+		}
+		if other.placeholder.is_some() {
+			self.placeholder = other.placeholder;
+		}
+		if other.rename.is_some() {
+			self.rename = other.rename;
+		}
+		if other.skip_if.is_some() {
+			self.skip_if = other.skip_if;
+		}
+		if other.strict.is_some() {
+			self.strict = other.strict;
+		}
 	}
 }
 
 impl Parse for FieldAttribute {
 	fn parse(input: ParseStream) -> syn::Result<Self> {
 		let mut result = Self::default();
-		let lookahead = input.lookahead1();
-
-		if !input.is_empty() {
-			if lookahead.peek(syn::LitStr) {
-				result.format = Some(input.parse()?);
-			} else if lookahead.peek(super::kw::ignore) {
-				let _kw: super::kw::ignore = input.parse()?;
-				result.ignore = true;
-				if !input.is_empty() {
-					let lookahead = input.lookahead1();
-					return Err(lookahead.error());
+
+		while !input.is_empty() {
+			if input.peek(syn::LitStr) {
+				let format: super::format::Format = input.parse()?;
+				result.keys.push(syn::Ident::new("format", format.string.span()));
+				result.format = Some(format);
+				break;
+			}
+
+			let key = super::parse_key(input, <Self as super::KeyValueAttribute>::KEYS)?;
+			result.keys.push(key.clone());
+			match key.to_string().as_str() {
+				"bound" => result.bound = Some(super::parse_bound(input)?),
+				"format" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.format = Some(input.parse()?);
+					break;
 				}
-			} else {
-				return Err(lookahead.error());
+				"ignore" => result.ignore = true,
+				"placeholder" => result.placeholder = Some(input.parse()?),
+				"redact" => result.redact = Some(input.parse()?),
+				"rename" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.rename = Some(input.parse()?);
+				}
+				"skip_if" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.skip_if = Some(input.parse()?);
+				}
+				"strict" => result.strict = Some(super::parse_strict(input)?),
+				"with" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.with = Some(input.parse()?);
+				}
+				_ => unreachable!("`parse_key` only accepts the given keys"),
+			}
+
+			if !input.is_empty() {
+				let _comma: syn::Token![,] = input.parse()?;
 			}
 		}
 
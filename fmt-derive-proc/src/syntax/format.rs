@@ -0,0 +1,886 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use proc_macro_error2::emit_error;
+use quote::{ToTokens, format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+
+/// A format string, followed by its (optional) explicit arguments, as it would be passed to `::core::format_args!`.
+#[derive(Clone, Debug)]
+pub struct Format {
+	pub string: syn::LitStr,
+	pub arguments: Vec<FormatArgument>,
+	placeholders: Vec<Placeholder>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FormatArgument {
+	pub name: Option<syn::Ident>,
+	pub expr: syn::Expr,
+}
+
+impl Parse for Format {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let string: syn::LitStr = input.parse()?;
+		let mut arguments = Vec::new();
+		while !input.is_empty() {
+			let _comma: syn::Token![,] = input.parse()?;
+			if input.is_empty() {
+				break;
+			}
+			arguments.push(input.parse()?);
+		}
+
+		let mut format = Format {
+			string,
+			arguments,
+			placeholders: Vec::new(),
+		};
+		format.placeholders = placeholders(&format.string.value()).map_err(|err| format.error(err))?;
+		format.validate()?;
+		Ok(format)
+	}
+}
+
+impl Parse for FormatArgument {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let name = if input.peek(syn::Ident::peek_any) && input.peek2(syn::Token![=]) && !input.peek2(syn::Token![==]) {
+			let name = input.call(syn::Ident::parse_any)?;
+			let _eq: syn::Token![=] = input.parse()?;
+			Some(name)
+		} else {
+			None
+		};
+		let expr = input.parse()?;
+
+		Ok(FormatArgument { name, expr })
+	}
+}
+
+impl ToTokens for Format {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		let string = &self.string;
+		let arguments = &self.arguments;
+		tokens.extend(quote!(#string #(, #arguments)*));
+	}
+}
+
+impl ToTokens for FormatArgument {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		if let Some(name) = &self.name {
+			tokens.extend(quote!(#name =));
+		}
+		self.expr.to_tokens(tokens);
+	}
+}
+
+/// A single `{...}` placeholder inside of a format string. All ranges are byte ranges relative to the unescaped string
+/// value.
+#[derive(Clone, Debug)]
+struct Placeholder {
+	/// The whole placeholder, including its braces.
+	range: Range<usize>,
+	/// The argument (the part before the `:`).
+	argument: Range<usize>,
+	/// The argument named by a width of the form `N$` or `name$`.
+	width: Option<Range<usize>>,
+	/// The precision, if it refers to an argument.
+	precision: Option<Count>,
+}
+
+impl Placeholder {
+	/// The ranges of the width and precision, if they refer to an argument by `N$` or `name$`.
+	fn counts(&self) -> impl Iterator<Item = &Range<usize>> {
+		self.width.iter().chain(match &self.precision {
+			Some(Count::Argument(range)) => Some(range),
+			_ => None,
+		})
+	}
+}
+
+/// A precision that refers to an argument instead of being given literally.
+#[derive(Clone, Debug)]
+enum Count {
+	/// `.N$` or `.name$`, with the range of `N` or `name`.
+	Argument(Range<usize>),
+	/// `.*`, with the range of the `*`, which takes the next positional argument.
+	Next(Range<usize>),
+}
+
+/// An error inside of a format string, together with the byte range it refers to.
+struct FormatError {
+	range: Range<usize>,
+	message: String,
+}
+
+impl FormatError {
+	fn new(range: Range<usize>, message: impl Into<String>) -> Self {
+		FormatError {
+			range,
+			message: message.into(),
+		}
+	}
+}
+
+/// The format traits that may be requested after the `:` of a placeholder.
+const FORMAT_TRAITS: &[&str] = &["", "?", "x?", "X?", "x", "X", "o", "b", "e", "E", "p"];
+
+/// Splits a format string into its placeholders, skipping over `{{` and `}}` escapes.
+fn placeholders(string: &str) -> Result<Vec<Placeholder>, FormatError> {
+	let mut result = Vec::new();
+	let mut chars = string.char_indices().peekable();
+	while let Some((index, c)) = chars.next() {
+		match c {
+			'{' => {
+				if let Some((_, '{')) = chars.peek() {
+					chars.next();
+					continue;
+				}
+				let start = index + 1;
+				let end = match string[start..].find('}') {
+					Some(offset) => start + offset,
+					None => {
+						return Err(FormatError::new(
+							index..string.len(),
+							"unterminated placeholder, expected `}`; use `{{` to print a literal `{`",
+						));
+					}
+				};
+				result.push(placeholder(string, index..end + 1)?);
+				while let Some((index, _)) = chars.peek() {
+					if *index > end {
+						break;
+					}
+					chars.next();
+				}
+			}
+			'}' => {
+				if let Some((_, '}')) = chars.peek() {
+					chars.next();
+				} else {
+					return Err(FormatError::new(
+						index..index + 1,
+						"unmatched `}` in format string, use `}}` to print a literal `}`",
+					));
+				}
+			}
+			_ => (),
+		}
+	}
+
+	Ok(result)
+}
+
+/// Parses the placeholder that spans `range` (including its braces), following the grammar of `std::fmt`:
+/// `{argument:[[fill]align][sign]['#']['0'][width]['.' precision]type}`.
+fn placeholder(string: &str, range: Range<usize>) -> Result<Placeholder, FormatError> {
+	let inner = range.start + 1..range.end - 1;
+	let (argument, spec) = match string[inner.clone()].find(':') {
+		Some(offset) => (inner.start..inner.start + offset, inner.start + offset + 1..inner.end),
+		None => (inner.clone(), inner.end..inner.end),
+	};
+
+	let text = &string[argument.clone()];
+	let valid_argument = text.is_empty()
+		|| text
+			.split('.')
+			.all(|segment| is_integer(segment) || is_identifier(segment));
+	if !valid_argument {
+		return Err(FormatError::new(
+			argument,
+			format!(
+				"invalid argument `{}` in format string, expected a position, a name or a field path",
+				text
+			),
+		));
+	}
+
+	let chars = string[spec.clone()]
+		.char_indices()
+		.map(|(offset, c)| (spec.start + offset, c))
+		.collect::<Vec<_>>();
+	let position = |index: usize| chars.get(index).map_or(spec.end, |(offset, _)| *offset);
+	let peek = |index: usize| chars.get(index).map(|(_, c)| *c);
+	let mut index = 0;
+
+	if matches!(peek(1), Some('<' | '^' | '>')) {
+		index = 2;
+	} else if matches!(peek(0), Some('<' | '^' | '>')) {
+		index = 1;
+	}
+	if matches!(peek(index), Some('+' | '-')) {
+		index += 1;
+	}
+	if peek(index) == Some('#') {
+		index += 1;
+	}
+	if peek(index) == Some('0') && peek(index + 1) != Some('$') {
+		index += 1;
+	}
+
+	// Parses a count (`N`, `N$` or `name$`), returning the range of the referenced argument, if any.
+	let count = |index: &mut usize| -> Option<Option<Range<usize>>> {
+		let start = *index;
+		let mut end = start;
+		match peek(start) {
+			Some(c) if c.is_ascii_digit() => {
+				while peek(end).is_some_and(|c| c.is_ascii_digit()) {
+					end += 1;
+				}
+			}
+			Some(c) if c == '_' || c.is_alphabetic() => {
+				while peek(end).is_some_and(|c| c == '_' || c.is_alphanumeric()) {
+					end += 1;
+				}
+				if peek(end) != Some('$') {
+					return None;
+				}
+			}
+			_ => return None,
+		}
+		if peek(end) == Some('$') {
+			*index = end + 1;
+			Some(Some(position(start)..position(end)))
+		} else {
+			*index = end;
+			Some(None)
+		}
+	};
+
+	let width = count(&mut index).flatten();
+
+	let mut precision = None;
+	if peek(index) == Some('.') {
+		index += 1;
+		if peek(index) == Some('*') {
+			precision = Some(Count::Next(position(index)..position(index + 1)));
+			index += 1;
+		} else {
+			match count(&mut index) {
+				Some(reference) => precision = reference.map(Count::Argument),
+				None => {
+					return Err(FormatError::new(
+						position(index - 1)..position(index),
+						"expected a precision after `.` in format string",
+					));
+				}
+			}
+		}
+	}
+
+	let format_trait = position(index)..spec.end;
+	if !FORMAT_TRAITS.contains(&&string[format_trait.clone()]) {
+		let expected = FORMAT_TRAITS[1..]
+			.iter()
+			.map(|name| format!("`{}`", name))
+			.collect::<Vec<_>>()
+			.join(", ");
+		return Err(FormatError::new(
+			format_trait.clone(),
+			format!(
+				"unknown format trait `{}`, expected nothing (for `Display`) or one of {}",
+				&string[format_trait], expected
+			),
+		));
+	}
+
+	Ok(Placeholder {
+		range,
+		argument,
+		width,
+		precision,
+	})
+}
+
+/// Translates the part of a placeholder after the `:` into a `defmt` display hint, or returns `None` if `defmt` has no
+/// equivalent. The format traits `Display` and `Debug` both map to no hint at all.
+#[cfg(feature = "defmt")]
+fn defmt_hint(spec: &str) -> Option<&str> {
+	if spec == "?" || spec == "#?" {
+		return Some("");
+	}
+	let rest = spec.strip_prefix('#').unwrap_or(spec);
+	let rest = match rest.strip_prefix('0') {
+		Some(width) if width.starts_with(|c: char| c.is_ascii_digit()) => {
+			width.trim_start_matches(|c: char| c.is_ascii_digit())
+		}
+		Some(_) => return None,
+		None => rest,
+	};
+	matches!(rest, "" | "x" | "X" | "o" | "b").then_some(spec)
+}
+
+/// Checks whether the part of a placeholder after the `:` can be passed to `ufmt::uwrite!` as is, which accepts
+/// `Display`, `Debug` (optionally with the alternate flag) and the formats `x` and `X` with the alternate flag, zero
+/// padding and a width. Unlike the other traits, the hexadecimal formats are not implemented for references, so their
+/// argument is replaced by the integer it refers to.
+#[cfg(feature = "ufmt")]
+fn ufmt_spec(spec: &str, argument: proc_macro2::TokenStream) -> Option<(&str, proc_macro2::TokenStream)> {
+	if spec.is_empty() || spec == "?" || spec == "#?" {
+		return Some((spec, argument));
+	}
+	let rest = spec.strip_prefix('#').unwrap_or(spec);
+	let rest = rest.strip_prefix('0').unwrap_or(rest);
+	let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+	matches!(rest, "x" | "X").then(|| (spec, quote!(_rt::UfmtHex::ufmt_hex(&(#argument)))))
+}
+
+fn is_integer(text: &str) -> bool {
+	!text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Checks whether `text` looks like an identifier. Keywords are allowed, as they may name raw fields such as `r#type`.
+fn is_identifier(text: &str) -> bool {
+	let mut chars = text.chars();
+	chars.next().is_some_and(|c| c == '_' || c.is_alphabetic())
+		&& chars.all(|c| c == '_' || c.is_alphanumeric())
+		&& text != "_"
+}
+
+impl Format {
+	/// Returns the span of a byte range of the string value. Pointing into a literal is only possible on nightly (where
+	/// `Literal::subspan` is implemented) and if the literal contains no escapes, so this falls back to the span of the
+	/// whole literal, which is what stable Rust always gets. Error messages therefore quote the offending part.
+	fn span(&self, range: Range<usize>) -> proc_macro2::Span {
+		let literal = self.string.token();
+		let repr = literal.to_string();
+		let (Some(open), Some(close)) = (repr.find('"'), repr.rfind('"')) else {
+			return self.string.span();
+		};
+		if open >= close || repr[open + 1..close] != self.string.value() {
+			return self.string.span();
+		}
+		literal
+			.subspan(open + 1 + range.start..open + 1 + range.end)
+			.unwrap_or_else(|| self.string.span())
+	}
+
+	fn error(&self, err: FormatError) -> syn::Error {
+		syn::Error::new(self.span(err.range), err.message)
+	}
+
+	/// Checks that every positional reference (`{}`, `{N}`, `{:N$}` and `{:.*}`) refers to an explicit argument and that
+	/// every explicit argument is used.
+	fn validate(&self) -> syn::Result<()> {
+		let value = self.string.value();
+		let mut errors = Vec::new();
+
+		let mut seen_named = false;
+		for (index, argument) in self.arguments.iter().enumerate() {
+			match &argument.name {
+				Some(name) => {
+					seen_named = true;
+					if self.arguments[..index]
+						.iter()
+						.any(|other| other.name.as_ref().is_some_and(|other| other.unraw() == name.unraw()))
+					{
+						errors.push(syn::Error::new_spanned(
+							name,
+							format!("duplicate argument named `{}`", name),
+						));
+					}
+				}
+				None if seen_named => errors.push(syn::Error::new_spanned(
+					&argument.expr,
+					"positional arguments cannot follow named arguments",
+				)),
+				None => (),
+			}
+		}
+
+		let has_positional_arguments = self.arguments.iter().any(|argument| argument.name.is_none());
+		let mut used = vec![false; self.arguments.len()];
+		let mut next = 0;
+		let mut reference = |text: &str, range: Range<usize>, placeholder: &Placeholder, errors: &mut Vec<syn::Error>| {
+			let index = if text.is_empty() {
+				next += 1;
+				next - 1
+			} else if let Ok(index) = text.parse::<usize>() {
+				index
+			} else {
+				if let Some(index) = self
+					.arguments
+					.iter()
+					.position(|argument| argument.name.as_ref().is_some_and(|name| name.unraw() == text))
+				{
+					used[index] = true;
+				}
+				return;
+			};
+
+			match used.get_mut(index) {
+				Some(used) => *used = true,
+				None => errors.push(self.error(FormatError::new(
+					range,
+					format!(
+						"`{}` refers to positional argument {}, but {}",
+						&value[placeholder.range.clone()],
+						index,
+						match self.arguments.len() {
+							0 => "no arguments were given".to_owned(),
+							1 => "there is only 1 argument".to_owned(),
+							count => format!("there are only {} arguments", count),
+						}
+					),
+				))),
+			}
+		};
+
+		for placeholder in &self.placeholders {
+			if let Some(Count::Next(range)) = &placeholder.precision {
+				reference("", range.clone(), placeholder, &mut errors);
+			}
+
+			let argument = &value[placeholder.argument.clone()];
+			if argument.is_empty() {
+				reference(argument, placeholder.range.clone(), placeholder, &mut errors);
+			} else if !argument.contains('.') && (has_positional_arguments || !is_integer(argument)) {
+				// Without explicit positional arguments, `{N}` refers to the tuple field `N` instead.
+				reference(argument, placeholder.argument.clone(), placeholder, &mut errors);
+			}
+
+			for range in placeholder.counts() {
+				let count = &value[range.clone()];
+				// Like `{N}`, `N$` refers to the tuple field `N` without explicit positional arguments.
+				if has_positional_arguments || !is_integer(count) {
+					reference(count, range.clone(), placeholder, &mut errors);
+				}
+			}
+		}
+
+		for (argument, used) in self.arguments.iter().zip(used) {
+			if !used {
+				errors.push(match &argument.name {
+					Some(name) => syn::Error::new_spanned(name, format!("named argument `{}` is never used", name)),
+					None => syn::Error::new_spanned(&argument.expr, "argument is never used by the format string"),
+				});
+			}
+		}
+
+		match errors.into_iter().reduce(|mut combined, error| {
+			combined.combine(error);
+			combined
+		}) {
+			Some(errors) => Err(errors),
+			None => Ok(()),
+		}
+	}
+
+	/// Rejects placeholders that access fields through `self` (e.g., `{self.x}`), which is used for the formats of `enum`
+	/// variants: `self` is the whole `enum` there, whose fields cannot be accessed directly.
+	pub fn reject_self_fields(&self) -> syn::Result<()> {
+		let value = self.string.value();
+		let errors = self.placeholders.iter().filter_map(|placeholder| {
+			let argument = &value[placeholder.argument.clone()];
+			let field = argument.strip_prefix("self.")?;
+			Some(self.error(FormatError::new(
+				placeholder.argument.clone(),
+				format!(
+					"`{{{}}}` cannot be used in the format of a variant, as `self` is the whole `enum`; use `{{{}}}` to \
+					 refer to the field of the variant",
+					argument, field
+				),
+			)))
+		});
+		match errors.reduce(|mut combined, error| {
+			combined.combine(error);
+			combined
+		}) {
+			Some(errors) => Err(errors),
+			None => Ok(()),
+		}
+	}
+
+	/// Expands to the arguments of a `::core::write!`/`::core::format_args!` call, resolving placeholders that name
+	/// fields (e.g., `{x}`, `{0}` or `{inner.name}`) against the variables that `fields` were destructured into.
+	pub fn expand(&self, fields: &syn::Fields) -> proc_macro2::TokenStream {
+		let value = self.string.value();
+		let span = self.string.span();
+
+		let has_positional_arguments = self.arguments.iter().any(|argument| argument.name.is_none());
+		let is_named_argument = |name: &str| {
+			self
+				.arguments
+				.iter()
+				.any(|argument| argument.name.as_ref().is_some_and(|ident| ident.unraw() == name))
+		};
+
+		let mut rewritten = String::with_capacity(value.len());
+		let mut captures = HashMap::<String, syn::Ident>::new();
+		let mut implicit_arguments = proc_macro2::TokenStream::new();
+		let mut position = 0;
+		let references = self.placeholders.iter().flat_map(|placeholder| {
+			let argument = Some(placeholder.argument.clone()).filter(|range| {
+				let argument = &value[range.clone()];
+				!argument.is_empty() && !is_named_argument(argument) && (!is_integer(argument) || !has_positional_arguments)
+			});
+			// counts that name a field or constant are captured by `format_args!` itself
+			let counts = placeholder
+				.counts()
+				.filter(|range| is_integer(&value[(*range).clone()]) && !has_positional_arguments)
+				.cloned();
+			argument.into_iter().chain(counts)
+		});
+		for range in references {
+			let argument = &value[range.clone()];
+			let Some(expr) = resolve(argument, fields, self.span(range.clone())) else {
+				continue;
+			};
+
+			let next_index = captures.len();
+			let name = captures
+				.entry(argument.to_owned())
+				.or_insert_with(|| {
+					let name = format_ident!("fmt_derive_argument_{}", next_index, span = span);
+					implicit_arguments.extend(quote!(, #name = #expr));
+					name
+				})
+				.to_string();
+			rewritten.push_str(&value[position..range.start]);
+			rewritten.push_str(&name);
+			position = range.end;
+		}
+		rewritten.push_str(&value[position..]);
+
+		let string = syn::LitStr::new(&rewritten, span);
+		let arguments = &self.arguments;
+		quote!(#string #(, #arguments)* #implicit_arguments)
+	}
+
+	/// Translates the format string into one for `defmt::write!`, returning the translated string together with one
+	/// argument per placeholder. Both `Debug` and `Display` placeholders print their argument with `defmt::Format`,
+	/// while the alternate flag, zero padding and the integer formats (`x`, `X`, `o` and `b`) become display hints.
+	/// Placeholders that use any other options are reported as errors, since `defmt` cannot reproduce them.
+	#[cfg(feature = "defmt")]
+	pub fn defmt(&self, fields: &syn::Fields) -> syn::Result<(String, Vec<proc_macro2::TokenStream>)> {
+		self.translate(
+			fields,
+			|spec, argument| Some((defmt_hint(spec)?, argument)),
+			"cannot be translated for `defmt`, which only supports the alternate flag, zero padding and the formats `x`, \
+			 `X`, `o` and `b`",
+		)
+	}
+
+	/// Translates the format string into one for `ufmt::uwrite!`, returning the translated string together with one
+	/// argument per placeholder. `Display` and `Debug` placeholders print their argument with `uDisplay` and `uDebug`,
+	/// respectively, and hexadecimal placeholders may use the alternate flag, zero padding and a width. Placeholders
+	/// that use any other options are reported as errors, since `ufmt` cannot reproduce them.
+	#[cfg(feature = "ufmt")]
+	pub fn ufmt(&self, fields: &syn::Fields) -> syn::Result<(String, Vec<proc_macro2::TokenStream>)> {
+		self.translate(
+			fields,
+			ufmt_spec,
+			"cannot be translated for `ufmt`, which only supports `{}`, `{:?}`, `{:#?}` and the formats `x` and `X` \
+			 with the alternate flag, zero padding and a width",
+		)
+	}
+
+	/// Translates the format string for a formatting macro other than `core::format_args!`, which receives one argument
+	/// per placeholder. `spec` translates the part of each placeholder after the `:` (where an empty result stands for
+	/// a plain `{}`) together with its argument, and returns `None` if the target cannot reproduce it, in which case an
+	/// error ending in `unsupported` is reported.
+	#[cfg(any(feature = "defmt", feature = "ufmt"))]
+	fn translate(
+		&self,
+		fields: &syn::Fields,
+		spec: fn(&str, proc_macro2::TokenStream) -> Option<(&str, proc_macro2::TokenStream)>,
+		unsupported: &str,
+	) -> syn::Result<(String, Vec<proc_macro2::TokenStream>)> {
+		let value = self.string.value();
+		let has_positional_arguments = self.arguments.iter().any(|argument| argument.name.is_none());
+		let mut errors = Vec::<syn::Error>::new();
+		let mut translated = String::with_capacity(value.len());
+		let mut arguments = Vec::new();
+		let mut position = 0;
+		let mut next = 0;
+
+		for placeholder in &self.placeholders {
+			translated.push_str(&value[position..placeholder.range.start]);
+			position = placeholder.range.end;
+
+			let argument = &value[placeholder.argument.clone()];
+			let explicit = if argument.is_empty() {
+				next += 1;
+				self.arguments.get(next - 1)
+			} else if is_integer(argument) && has_positional_arguments {
+				argument
+					.parse::<usize>()
+					.ok()
+					.and_then(|index| self.arguments.get(index))
+			} else {
+				self
+					.arguments
+					.iter()
+					.find(|explicit| explicit.name.as_ref().is_some_and(|name| name.unraw() == argument))
+			};
+			let argument = match explicit {
+				Some(explicit) => {
+					let expr = &explicit.expr;
+					quote!(#expr)
+				}
+				None => match resolve(argument, fields, self.span(placeholder.argument.clone())) {
+					Some(expr) => expr,
+					None => continue,
+				},
+			};
+
+			let placeholder_spec = value[placeholder.argument.end..placeholder.range.end - 1].trim_start_matches(':');
+			match spec(placeholder_spec, argument) {
+				Some((spec, argument)) => {
+					if spec.is_empty() {
+						translated.push_str("{}");
+					} else {
+						translated.push_str("{:");
+						translated.push_str(spec);
+						translated.push('}');
+					}
+					arguments.push(argument);
+				}
+				None => errors.push(self.error(FormatError::new(
+					placeholder.range.clone(),
+					format!("`{}` {}", &value[placeholder.range.clone()], unsupported),
+				))),
+			}
+		}
+		translated.push_str(&value[position..]);
+
+		match errors.into_iter().reduce(|mut combined, error| {
+			combined.combine(error);
+			combined
+		}) {
+			Some(errors) => Err(errors),
+			None => Ok((translated, arguments)),
+		}
+	}
+}
+
+/// A part of a format string, as matched by the derived `FromStr` implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+	/// Text that is printed as is, with `{{` and `}}` already unescaped.
+	Literal(String),
+	/// A placeholder that prints the field with the given index using `Display` and no further options.
+	Field(usize),
+}
+
+impl Format {
+	/// Splits the format string into literal text and placeholders that print a field of `fields` directly, so that the
+	/// output can be parsed again. Placeholders that refer to anything else, that give formatting options or that
+	/// directly follow another field are reported as errors, since their output cannot be mapped back to a field.
+	pub fn segments(&self, fields: &syn::Fields) -> syn::Result<Vec<Segment>> {
+		let value = self.string.value();
+		let has_positional_arguments = self.arguments.iter().any(|argument| argument.name.is_none());
+		let mut errors = Vec::<syn::Error>::new();
+		let mut segments = Vec::new();
+		let mut position = 0;
+		let mut next = 0;
+
+		let literal = |text: &str, segments: &mut Vec<Segment>| {
+			let text = text.replace("{{", "{").replace("}}", "}");
+			if text.is_empty() {
+				return;
+			}
+			match segments.last_mut() {
+				Some(Segment::Literal(last)) => last.push_str(&text),
+				_ => segments.push(Segment::Literal(text)),
+			}
+		};
+
+		for placeholder in &self.placeholders {
+			literal(&value[position..placeholder.range.start], &mut segments);
+			position = placeholder.range.end;
+
+			let spec = &value[placeholder.argument.end..placeholder.range.end - 1];
+			if !spec.is_empty() && spec != ":" {
+				errors.push(self.error(FormatError::new(
+					placeholder.range.clone(),
+					format!(
+						"`{}` cannot be parsed, as `FromStr` only supports placeholders without formatting options",
+						&value[placeholder.range.clone()]
+					),
+				)));
+				continue;
+			}
+
+			let argument = &value[placeholder.argument.clone()];
+			let explicit = if argument.is_empty() {
+				next += 1;
+				self.arguments.get(next - 1)
+			} else if is_integer(argument) && has_positional_arguments {
+				argument
+					.parse::<usize>()
+					.ok()
+					.and_then(|index| self.arguments.get(index))
+			} else {
+				self
+					.arguments
+					.iter()
+					.find(|explicit| explicit.name.as_ref().is_some_and(|name| name.unraw() == argument))
+			};
+			let field = match explicit {
+				Some(explicit) => argument_field(&explicit.expr, fields),
+				None => argument
+					.strip_prefix("self.")
+					.or(Some(argument))
+					.and_then(|name| field_index(name, fields)),
+			};
+			let adjacent = matches!(segments.last(), Some(Segment::Field(_)));
+			match field {
+				Some(_) if adjacent => errors.push(self.error(FormatError::new(
+					placeholder.range.clone(),
+					format!(
+						"`{}` cannot be parsed, as it directly follows another field, whose end would be ambiguous",
+						&value[placeholder.range.clone()]
+					),
+				))),
+				Some(index) => segments.push(Segment::Field(index)),
+				None => errors.push(self.error(FormatError::new(
+					placeholder.range.clone(),
+					format!(
+						"`{}` cannot be parsed, as `FromStr` only supports placeholders that refer to a field directly",
+						&value[placeholder.range.clone()]
+					),
+				))),
+			}
+		}
+		literal(&value[position..], &mut segments);
+
+		match errors.into_iter().reduce(|mut combined, error| {
+			combined.combine(error);
+			combined
+		}) {
+			Some(errors) => Err(errors),
+			None => Ok(segments),
+		}
+	}
+}
+
+/// Returns the index of the field that an explicit argument such as `x`, `_0`, `self.x` or `self.0` refers to.
+fn argument_field(expr: &syn::Expr, fields: &syn::Fields) -> Option<usize> {
+	match expr {
+		syn::Expr::Path(path) => {
+			let ident = path.path.get_ident()?.unraw().to_string();
+			match fields {
+				syn::Fields::Unnamed(_) => field_index(ident.strip_prefix('_')?, fields),
+				_ => field_index(&ident, fields),
+			}
+		}
+		syn::Expr::Field(field) => match &*field.base {
+			syn::Expr::Path(base) if base.path.is_ident("self") => match &field.member {
+				syn::Member::Named(ident) => field_index(&ident.unraw().to_string(), fields),
+				syn::Member::Unnamed(index) => field_index(&index.index.to_string(), fields),
+			},
+			_ => None,
+		},
+		syn::Expr::Paren(paren) => argument_field(&paren.expr, fields),
+		_ => None,
+	}
+}
+
+/// Returns the index of the field named `name`, e.g., `0` for the first tuple field.
+fn field_index(name: &str, fields: &syn::Fields) -> Option<usize> {
+	match fields {
+		syn::Fields::Unit => None,
+		syn::Fields::Unnamed(fields) => name
+			.parse::<usize>()
+			.ok()
+			.filter(|index| is_integer(name) && *index < fields.unnamed.len()),
+		syn::Fields::Named(fields) => fields
+			.named
+			.iter()
+			.position(|field| field.ident.as_ref().is_some_and(|ident| ident.unraw() == name)),
+	}
+}
+
+/// Resolves a placeholder argument such as `x`, `0`, `self.x` or `inner.name` to an expression in terms of the
+/// destructured field variables. A plain identifier that does not name a field is captured from the surrounding scope
+/// (e.g., a constant `{MAX}`), as `format_args!` would do. Emits an error and returns `None` if the argument can
+/// neither be resolved nor captured.
+fn resolve(argument: &str, fields: &syn::Fields, span: proc_macro2::Span) -> Option<proc_macro2::TokenStream> {
+	let mut segments = argument.split('.');
+	let head = segments.next().expect("`split` always yields at least one segment");
+
+	let mut expr = if head == "self" {
+		if !argument.contains('.') {
+			emit_error!(
+				span, "`{{self}}` cannot be used in a format string";
+				help = "use `{{self.field}}` to refer to a field"
+			);
+			return None;
+		}
+		quote!(self)
+	} else {
+		match field_variable(head, fields) {
+			Some(ident) => quote!(#ident),
+			None if !argument.contains('.') && !is_integer(head) => match syn::parse_str::<syn::Ident>(head) {
+				Ok(mut ident) => {
+					ident.set_span(span);
+					return Some(quote!(#ident));
+				}
+				Err(_) => {
+					emit_error!(span, "invalid argument `{}` in format string", head);
+					return None;
+				}
+			},
+			None => {
+				let available = fields
+					.iter()
+					.enumerate()
+					.map(|(index, field)| match &field.ident {
+						Some(ident) => format!("`{}`", ident.unraw()),
+						None => format!("`{}`", index),
+					})
+					.collect::<Vec<_>>();
+				if available.is_empty() {
+					emit_error!(span, "unknown field `{}` in format string", head; note = "there are no fields in scope");
+				} else {
+					emit_error!(
+						span, "unknown field `{}` in format string", head;
+						note = "available fields are: {}", available.join(", ")
+					);
+				}
+				return None;
+			}
+		}
+	};
+
+	for segment in segments {
+		if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+			let index = syn::Index {
+				index: segment.parse().ok()?,
+				span,
+			};
+			expr.extend(quote!(.#index));
+		} else if syn::parse_str::<syn::Ident>(segment).is_ok() {
+			let ident = syn::Ident::new(segment, span);
+			expr.extend(quote!(.#ident));
+		} else {
+			emit_error!(span, "invalid field access `{}` in format string", argument);
+			return None;
+		}
+	}
+
+	Some(expr)
+}
+
+/// Returns the variable a field was destructured into, i.e., `_0` for the first tuple field or `x` for the field `x`.
+fn field_variable(name: &str, fields: &syn::Fields) -> Option<syn::Ident> {
+	match fields {
+		syn::Fields::Unit => None,
+		syn::Fields::Unnamed(fields) => {
+			let index = name.parse::<usize>().ok()?;
+			if index < fields.unnamed.len() && name.bytes().all(|b| b.is_ascii_digit()) {
+				Some(format_ident!("_{}", index))
+			} else {
+				None
+			}
+		}
+		syn::Fields::Named(fields) => fields
+			.named
+			.iter()
+			.filter_map(|field| field.ident.as_ref())
+			.find(|ident| ident.unraw() == name)
+			.cloned(),
+	}
+}
@@ -1,12 +1,98 @@
 use syn::parse::{Parse, ParseStream};
 
+use super::placeholder::Placeholder;
+use crate::layout::Layout;
+use crate::non_exhaustive::NonExhaustive;
+use crate::rename::RenameRule;
+use crate::strict::Strict;
+
 #[derive(Clone, Debug, Default)]
 pub struct ItemAttribute {
-	pub format: Option<proc_macro2::TokenStream>,
+	pub keys: Vec<syn::Ident>,
+	pub bound: Option<super::Bound>,
+	pub infer_bounds: bool,
+	pub layout: Option<Layout>,
+	pub non_exhaustive: Option<NonExhaustive>,
+	pub placeholder: Option<Placeholder>,
+	pub rename: Option<syn::LitStr>,
+	pub rename_all: Option<RenameRule>,
+	pub pad: bool,
+	pub strict: Option<Strict>,
+	pub transparent: bool,
+	pub union_bytes: bool,
+	pub variant: Option<syn::Expr>,
+	pub format: Option<super::format::Format>,
 }
 
-impl ItemAttribute {
-	pub fn update(&mut self, other: Self) {
+impl super::KeyValueAttribute for ItemAttribute {
+	const KEYS: &'static [&'static str] = &[
+		"bound",
+		"format",
+		"infer_bounds",
+		"layout",
+		"non_exhaustive",
+		"pad",
+		"placeholder",
+		"rename",
+		"rename_all",
+		"strict",
+		"transparent",
+		"union_bytes",
+		"unsafe",
+		"variant",
+	];
+	const EXCLUSIVE: &'static [&'static [&'static str]] = &[
+		&["format", "transparent", "union_bytes", "variant"],
+		&["format", "layout"],
+		&["format", "non_exhaustive"],
+		&["layout", "transparent", "union_bytes"],
+		&["non_exhaustive", "transparent"],
+		&["non_exhaustive", "union_bytes"],
+		&["pad", "transparent"],
+	];
+
+	fn keys(&self) -> &[syn::Ident] {
+		&self.keys
+	}
+
+	fn update(&mut self, other: Self) {
+		self.keys.extend(other.keys);
+		if other.bound.is_some() {
+			self.bound = other.bound;
+		}
+		if other.infer_bounds {
+			self.infer_bounds = true;
+		}
+		if other.layout.is_some() {
+			self.layout = other.layout;
+		}
+		if other.non_exhaustive.is_some() {
+			self.non_exhaustive = other.non_exhaustive;
+		}
+		if other.placeholder.is_some() {
+			self.placeholder = other.placeholder;
+		}
+		if other.rename.is_some() {
+			self.rename = other.rename;
+		}
+		if other.rename_all.is_some() {
+			self.rename_all = other.rename_all;
+		}
+		if other.strict.is_some() {
+			self.strict = other.strict;
+		}
+		if other.pad {
+			self.pad = true;
+		}
+		if other.transparent {
+			self.transparent = true;
+		}
+		if other.union_bytes {
+			self.union_bytes = true;
+		}
+		if other.variant.is_some() {
+			self.variant = other.variant;
+		}
 		if other.format.is_some() {
 			self.format = other.format;
 		}
@@ -16,16 +102,115 @@ impl ItemAttribute {
 impl Parse for ItemAttribute {
 	fn parse(input: ParseStream) -> syn::Result<Self> {
 		let mut result = Self::default();
-		let lookahead = input.lookahead1();
 
-		if !input.is_empty() {
-			if lookahead.peek(syn::LitStr) {
-				result.format = Some(input.parse()?);
-			} else {
-				return Err(lookahead.error());
+		while !input.is_empty() {
+			if input.peek(syn::LitStr) {
+				let format: super::format::Format = input.parse()?;
+				result.keys.push(syn::Ident::new("format", format.string.span()));
+				result.format = Some(format);
+				break;
+			}
+
+			let key = super::parse_key(input, <Self as super::KeyValueAttribute>::KEYS)?;
+			if key == "unsafe" {
+				result.parse_unsafe(input)?;
+				if !input.is_empty() {
+					let _comma: syn::Token![,] = input.parse()?;
+				}
+				continue;
+			}
+			result.keys.push(key.clone());
+			match key.to_string().as_str() {
+				"bound" => result.bound = Some(super::parse_bound(input)?),
+				"format" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.format = Some(input.parse()?);
+					break;
+				}
+				"infer_bounds" => result.infer_bounds = true,
+				"layout" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.layout = Some(input.parse()?);
+				}
+				"non_exhaustive" => {
+					result.non_exhaustive = Some(if input.peek(syn::Token![=]) {
+						let _eq: syn::Token![=] = input.parse()?;
+						input.parse()?
+					} else {
+						NonExhaustive::Always
+					});
+				}
+				"rename" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.rename = Some(input.parse()?);
+				}
+				"rename_all" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.rename_all = Some(input.parse()?);
+				}
+				"pad" => result.pad = true,
+				"placeholder" => result.placeholder = Some(input.parse()?),
+				"strict" => result.strict = Some(super::parse_strict(input)?),
+				"transparent" => result.transparent = true,
+				"union_bytes" => {
+					return Err(syn::Error::new(
+						key.span(),
+						"`union_bytes` reads all bytes of the union, which is unsafe, so it must be given as \
+						 `unsafe(union_bytes)`",
+					));
+				}
+				"variant" => {
+					return Err(syn::Error::new(
+						key.span(),
+						"`variant` selects the field to read from the union, which is unsafe, so it must be given as \
+						 `unsafe(variant = ...)`",
+					));
+				}
+				_ => unreachable!("`parse_key` only accepts the given keys"),
+			}
+
+			if !input.is_empty() {
+				let _comma: syn::Token![,] = input.parse()?;
 			}
 		}
 
 		Ok(result)
 	}
 }
+
+impl ItemAttribute {
+	/// The keys that can only be given inside of `unsafe(...)`, as the derived implementation reads the memory of a
+	/// union for them.
+	const UNSAFE_KEYS: &'static [&'static str] = &["union_bytes", "variant"];
+
+	/// The keys that were given inside of `unsafe(...)`.
+	pub fn unsafe_keys(&self) -> impl Iterator<Item = &syn::Ident> {
+		self
+			.keys
+			.iter()
+			.filter(|key| Self::UNSAFE_KEYS.iter().any(|name| *key == name))
+	}
+
+	/// Parses the parenthesized keys following `unsafe`.
+	fn parse_unsafe(&mut self, input: ParseStream) -> syn::Result<()> {
+		let content;
+		syn::parenthesized!(content in input);
+		while !content.is_empty() {
+			let key = super::parse_key(&content, Self::UNSAFE_KEYS)?;
+			self.keys.push(key.clone());
+			match key.to_string().as_str() {
+				"union_bytes" => self.union_bytes = true,
+				"variant" => {
+					let _eq: syn::Token![=] = content.parse()?;
+					self.variant = Some(content.parse()?);
+				}
+				_ => unreachable!("`parse_key` only accepts the given keys"),
+			}
+
+			if !content.is_empty() {
+				let _comma: syn::Token![,] = content.parse()?;
+			}
+		}
+		Ok(())
+	}
+}
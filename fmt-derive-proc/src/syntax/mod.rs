@@ -1,7 +1,180 @@
+use proc_macro_error2::emit_error;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+
+pub mod error_attribute;
 pub mod field_attribute;
+pub mod format;
 pub mod item_attribute;
+pub mod placeholder;
+pub mod redact;
 pub mod variant_attribute;
 
+/// The where predicates given by `bound = "..."`.
+pub type Bound = syn::punctuated::Punctuated<syn::WherePredicate, syn::Token![,]>;
+
+/// Parses the `= "..."` part of `bound = "..."`.
+fn parse_bound(input: ParseStream) -> syn::Result<Bound> {
+	let _eq: syn::Token![=] = input.parse()?;
+	let literal: syn::LitStr = input.parse()?;
+	literal.parse_with(Bound::parse_terminated)
+}
+
+/// Parses the optional `= "..."` part of `strict = "..."`, where a bare `strict` means `strict = "deny"`.
+fn parse_strict(input: ParseStream) -> syn::Result<crate::strict::Strict> {
+	if input.peek(syn::Token![=]) {
+		let _eq: syn::Token![=] = input.parse()?;
+		input.parse()
+	} else {
+		Ok(crate::strict::Strict::Deny)
+	}
+}
+
+/// An attribute that consists of a comma separated list of keys, most of which are followed by a value.
+pub trait KeyValueAttribute: Parse + Default {
+	/// All keys that may be given. The key `format` may also be given as a plain string literal.
+	const KEYS: &'static [&'static str];
+	/// Groups of keys that contradict each other, so that at most one key of each group may be given.
+	const EXCLUSIVE: &'static [&'static [&'static str]];
+
+	/// The keys that were given, in order.
+	fn keys(&self) -> &[syn::Ident];
+
+	/// Merges `other` into `self`, with the values given in `other` taking precedence.
+	fn update(&mut self, other: Self);
+}
+
+/// Parses and merges all attributes named one of `names`, which are given in ascending order of precedence, so that a
+/// key given in `#[debug(...)]` overrides the same key given in `#[fmt(...)]`. Giving a key more than once for the same
+/// name, or giving contradictory keys for the same name, is an error.
+pub fn parse_attributes<T: KeyValueAttribute>(attributes: &[syn::Attribute], names: &[&str]) -> T {
+	let mut result = T::default();
+	for name in names {
+		let mut merged = T::default();
+		for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident(name)) {
+			match attribute.parse_args() {
+				Ok(value) => merged.update(value),
+				Err(err) => emit_error!(err),
+			}
+		}
+		check_keys(&merged, name, names);
+		result.update(merged);
+	}
+	result
+}
+
+/// Reports duplicate and contradictory keys among all keys given for the attribute `name`.
+fn check_keys<T: KeyValueAttribute>(attribute: &T, name: &str, names: &[&str]) {
+	let precedence = match names.split_last() {
+		Some((last, rest)) if !rest.is_empty() => format!(
+			"keys given in `#[{}(...)]` take precedence over those given in {}",
+			last,
+			rest
+				.iter()
+				.map(|name| format!("`#[{}(...)]`", name))
+				.collect::<Vec<_>>()
+				.join(" and ")
+		),
+		_ => format!("all `#[{}(...)]` attributes of an item are merged", name),
+	};
+
+	let keys = attribute.keys();
+	for (index, key) in keys.iter().enumerate() {
+		if let Some(first) = keys[..index].iter().find(|first| *first == key) {
+			emit_error!(
+				key, "`{}` is given more than once in `#[{}(...)]`", key, name;
+				span_note = first.span() => "first given here";
+				note = precedence
+			);
+		}
+	}
+
+	for group in T::EXCLUSIVE {
+		let mut given = keys.iter().filter(|key| group.iter().any(|name| *key == name));
+		let Some(first) = given.next() else {
+			continue;
+		};
+		for key in given.filter(|key| *key != first) {
+			let alternatives = group.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>();
+			emit_error!(
+				key, "`{}` contradicts `{}` in `#[{}(...)]`", key, first, name;
+				span_note = first.span() => "`{}` is given here", first;
+				help = "only one of {} may be given", alternatives.join(", ");
+				note = precedence
+			);
+		}
+	}
+}
+
+/// Parses the next key of an attribute, which must be one of `keys`.
+fn parse_key(input: ParseStream, keys: &[&str]) -> syn::Result<syn::Ident> {
+	let mut expected = keys
+		.iter()
+		.map(|key| format!("`{}`", key))
+		.collect::<Vec<_>>()
+		.join(", ");
+	if keys.contains(&"format") {
+		expected = format!("a format string or one of {}", expected);
+	} else {
+		expected = format!("one of {}", expected);
+	}
+
+	if !input.peek(syn::Ident::peek_any) {
+		return Err(input.error(format!("expected {}", expected)));
+	}
+	let key = input.call(syn::Ident::parse_any)?;
+	let name = key.to_string();
+	if keys.contains(&name.as_str()) {
+		return Ok(key);
+	}
+
+	let message = match did_you_mean(&name, keys) {
+		Some(suggestion) => format!("unknown key `{}`, did you mean `{}`?", name, suggestion),
+		None => format!("unknown key `{}`, expected {}", name, expected),
+	};
+	Err(syn::Error::new(key.span(), message))
+}
+
+/// Returns the candidate that is closest to `name`, if it is close enough to be a plausible typo.
+fn did_you_mean<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+	candidates
+		.iter()
+		.map(|candidate| (edit_distance(name, candidate), *candidate))
+		.filter(|(distance, candidate)| *distance <= candidate.len().max(name.len()).div_ceil(3))
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, candidate)| candidate)
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent characters that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let a = a.chars().collect::<Vec<_>>();
+	let b = b.chars().collect::<Vec<_>>();
+	let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in distances.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	distances[0] = (0..=b.len()).collect();
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			let mut distance = (distances[i - 1][j] + 1)
+				.min(distances[i][j - 1] + 1)
+				.min(distances[i - 1][j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				distance = distance.min(distances[i - 2][j - 2] + 1);
+			}
+			distances[i][j] = distance;
+		}
+	}
+	distances[a.len()][b.len()]
+}
+
 mod kw {
-	syn::custom_keyword!(ignore);
+	syn::custom_keyword!(last);
+	syn::custom_keyword!(length);
+	syn::custom_keyword!(path);
+	syn::custom_keyword!(short);
+	syn::custom_keyword!(text);
+	syn::custom_keyword!(type_name);
+	syn::custom_keyword!(with);
 }
@@ -0,0 +1,219 @@
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::visit_mut::VisitMut;
+
+/// The text printed for a field whose type does not implement the derived trait, as given by `placeholder(path)`,
+/// `placeholder(short)`, `placeholder(type_name)`, `placeholder(text = "...")` or `placeholder(with = path)`.
+#[derive(Clone, Debug, Default)]
+pub enum Placeholder {
+	/// The type as written, e.g., `<Vec<foo::Foo>>`.
+	#[default]
+	Path,
+	/// The type without any module paths, e.g., `<Vec<Foo>>`.
+	Short,
+	/// The type as returned by `core::any::type_name` at runtime, e.g., `<alloc::vec::Vec<my_crate::foo::Foo>>`.
+	TypeName,
+	/// A fixed text.
+	Text(syn::LitStr),
+	/// A function `fn(&str, &mut Formatter) -> fmt::Result` that is called with the result of `core::any::type_name`.
+	With(syn::ExprPath),
+}
+
+impl Parse for Placeholder {
+	/// Parses everything following the `placeholder` keyword.
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let content;
+		syn::parenthesized!(content in input);
+		Self::parse_argument(&content)
+	}
+}
+
+impl Placeholder {
+	/// The placeholder given by `placeholder = "..."` in the `[package.metadata.fmt-derive]` table of the manifest of
+	/// the crate that uses the derives, which uses the same syntax as the parentheses of `placeholder(...)`, or the
+	/// default if it is not set.
+	pub fn from_manifest() -> syn::Result<Self> {
+		let placeholder = crate::manifest::metadata("placeholder", |value| {
+			syn::parse::Parser::parse_str(Self::parse_argument, value)
+		})?;
+		Ok(placeholder.unwrap_or_default())
+	}
+
+	/// Parses the argument of `placeholder(...)`.
+	fn parse_argument(input: ParseStream) -> syn::Result<Self> {
+		let lookahead = input.lookahead1();
+		let result = if lookahead.peek(super::kw::path) {
+			let _kw: super::kw::path = input.parse()?;
+			Placeholder::Path
+		} else if lookahead.peek(super::kw::short) {
+			let _kw: super::kw::short = input.parse()?;
+			Placeholder::Short
+		} else if lookahead.peek(super::kw::type_name) {
+			let _kw: super::kw::type_name = input.parse()?;
+			Placeholder::TypeName
+		} else if lookahead.peek(super::kw::text) {
+			let _kw: super::kw::text = input.parse()?;
+			let _eq: syn::Token![=] = input.parse()?;
+			Placeholder::Text(input.parse()?)
+		} else if lookahead.peek(super::kw::with) {
+			let _kw: super::kw::with = input.parse()?;
+			let _eq: syn::Token![=] = input.parse()?;
+			Placeholder::With(input.parse()?)
+		} else {
+			return Err(lookahead.error());
+		};
+
+		if !input.is_empty() {
+			return Err(input.error("unexpected token after placeholder"));
+		}
+
+		Ok(result)
+	}
+
+	/// Expands to an expression of type `&dyn Display` that prints the placeholder for a field of type `ty`.
+	pub fn expand(&self, ty: &syn::Type) -> TokenStream {
+		match self {
+			Placeholder::Path | Placeholder::Short | Placeholder::Text(_) => {
+				let text = self.text(ty).expect("the placeholder is a fixed text");
+				quote!(&#text)
+			}
+			Placeholder::TypeName => quote!(&_rt::TypeName(::core::any::type_name::<#ty>())),
+			Placeholder::With(path) => quote!(&_rt::PlaceholderWith(::core::any::type_name::<#ty>(), #path)),
+		}
+	}
+
+	/// Expands to an expression of type `_rt::DefmtPlaceholder` that prints the placeholder for a field of type `ty`
+	/// with `defmt`, which interns fixed texts. Fails for `placeholder(with = path)`, as `defmt` has no
+	/// `core::fmt::Formatter` to pass to the function.
+	#[cfg(feature = "defmt")]
+	pub fn expand_defmt(&self, ty: &syn::Type) -> syn::Result<TokenStream> {
+		match self {
+			Placeholder::Path | Placeholder::Short | Placeholder::Text(_) => {
+				let text = self.text(ty).expect("the placeholder is a fixed text");
+				Ok(quote!(_rt::DefmtPlaceholder::Text(defmt::intern!(#text))))
+			}
+			Placeholder::TypeName => Ok(quote!(_rt::DefmtPlaceholder::TypeName(::core::any::type_name::<#ty>()))),
+			Placeholder::With(path) => Err(syn::Error::new_spanned(
+				path,
+				"`placeholder(with = ...)` cannot be used with `defmt`",
+			)),
+		}
+	}
+
+	/// Expands to an expression of type `_rt::UfmtPlaceholder` that prints the placeholder for a field of type `ty` with
+	/// `ufmt`. Fails for `placeholder(with = path)`, as `ufmt` has no `core::fmt::Formatter` to pass to the function.
+	#[cfg(feature = "ufmt")]
+	pub fn expand_ufmt(&self, ty: &syn::Type) -> syn::Result<TokenStream> {
+		match self {
+			Placeholder::Path | Placeholder::Short | Placeholder::Text(_) => {
+				let text = self.text(ty).expect("the placeholder is a fixed text");
+				Ok(quote!(_rt::UfmtPlaceholder::Text(#text)))
+			}
+			Placeholder::TypeName => Ok(quote!(_rt::UfmtPlaceholder::TypeName(::core::any::type_name::<#ty>()))),
+			Placeholder::With(path) => Err(syn::Error::new_spanned(
+				path,
+				"`placeholder(with = ...)` cannot be used with `ufmt`",
+			)),
+		}
+	}
+
+	/// The fixed text of the placeholder for a field of type `ty`, if it does not depend on the type at runtime.
+	fn text(&self, ty: &syn::Type) -> Option<syn::LitStr> {
+		let text = match self {
+			Placeholder::Path => format!("<{}>", type_string(quote!(#ty))),
+			Placeholder::Short => {
+				let mut ty = ty.clone();
+				ShortenPaths.visit_type_mut(&mut ty);
+				format!("<{}>", type_string(quote!(#ty)))
+			}
+			Placeholder::Text(text) => return Some(text.clone()),
+			Placeholder::TypeName | Placeholder::With(_) => return None,
+		};
+		Some(syn::LitStr::new(&text, proc_macro2::Span::call_site()))
+	}
+}
+
+/// Removes the module paths from all paths in a type, keeping only their last segment.
+struct ShortenPaths;
+
+impl VisitMut for ShortenPaths {
+	fn visit_type_path_mut(&mut self, type_path: &mut syn::TypePath) {
+		if type_path.qself.is_none() {
+			if let Some(last) = type_path.path.segments.pop() {
+				type_path.path.leading_colon = None;
+				type_path.path.segments.clear();
+				type_path.path.segments.push(last.into_value());
+			}
+		}
+		syn::visit_mut::visit_type_path_mut(self, type_path);
+	}
+}
+
+/// Prints the tokens of a type the way `rustfmt` would, which, unlike `TokenStream::to_string`, does not depend on the
+/// compiler version.
+pub fn type_string(tokens: TokenStream) -> String {
+	let mut result = String::new();
+	write_tokens(tokens, &mut result);
+	result
+}
+
+fn write_tokens(tokens: TokenStream, result: &mut String) {
+	let ends_with_word = |result: &String| result.ends_with(|c: char| c.is_alphanumeric() || c == '_');
+	let mut tokens = tokens.into_iter().peekable();
+	while let Some(token) = tokens.next() {
+		match token {
+			TokenTree::Group(group) => {
+				let (open, close) = match group.delimiter() {
+					Delimiter::Parenthesis => ("(", ")"),
+					Delimiter::Bracket => ("[", "]"),
+					Delimiter::Brace => ("{ ", " }"),
+					Delimiter::None => ("", ""),
+				};
+				if group.delimiter() == Delimiter::Brace && ends_with_word(result) {
+					result.push(' ');
+				}
+				result.push_str(open);
+				write_tokens(group.stream(), result);
+				result.truncate(result.trim_end().len());
+				result.push_str(close);
+			}
+			TokenTree::Punct(punct) => match punct.as_char() {
+				'-'
+					if punct.spacing() == Spacing::Joint
+						&& matches!(tokens.peek(), Some(TokenTree::Punct(next)) if next.as_char() == '>') =>
+				{
+					tokens.next();
+					result.push_str(" -> ");
+				}
+				c @ ('=' | '+') => {
+					result.truncate(result.trim_end().len());
+					result.push(' ');
+					result.push(c);
+					result.push(' ');
+				}
+				c @ (',' | ';') => {
+					result.push(c);
+					result.push(' ');
+				}
+				'>' => {
+					result.truncate(result.trim_end().len());
+					result.push('>');
+				}
+				c => result.push(c),
+			},
+			TokenTree::Ident(ident) => {
+				if ends_with_word(result) {
+					result.push(' ');
+				}
+				result.push_str(&ident.to_string());
+			}
+			TokenTree::Literal(literal) => {
+				if ends_with_word(result) {
+					result.push(' ');
+				}
+				result.push_str(&literal.to_string());
+			}
+		}
+	}
+}
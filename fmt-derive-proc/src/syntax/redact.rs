@@ -0,0 +1,58 @@
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+
+/// The redaction strategy given by `redact`, `redact(length)`, `redact(last = N)` or `redact(with = path)`.
+#[derive(Clone, Debug)]
+pub enum Redact {
+	Fixed,
+	Length,
+	Last(syn::LitInt),
+	With(syn::ExprPath),
+}
+
+impl Parse for Redact {
+	/// Parses everything following the `redact` keyword.
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		if !input.peek(syn::token::Paren) {
+			return Ok(Redact::Fixed);
+		}
+
+		let content;
+		syn::parenthesized!(content in input);
+		let lookahead = content.lookahead1();
+		let result = if lookahead.peek(super::kw::length) {
+			let _kw: super::kw::length = content.parse()?;
+			Redact::Length
+		} else if lookahead.peek(super::kw::last) {
+			let _kw: super::kw::last = content.parse()?;
+			let _eq: syn::Token![=] = content.parse()?;
+			let count: syn::LitInt = content.parse()?;
+			count.base10_parse::<usize>()?;
+			Redact::Last(count)
+		} else if lookahead.peek(super::kw::with) {
+			let _kw: super::kw::with = content.parse()?;
+			let _eq: syn::Token![=] = content.parse()?;
+			Redact::With(content.parse()?)
+		} else {
+			return Err(lookahead.error());
+		};
+
+		if !content.is_empty() {
+			return Err(content.error("unexpected token after redaction strategy"));
+		}
+
+		Ok(result)
+	}
+}
+
+impl Redact {
+	/// Expands to an expression implementing `Debug` that prints the redacted form of the variable `var_name`.
+	pub fn expand(&self, var_name: &syn::Ident) -> proc_macro2::TokenStream {
+		match self {
+			Redact::Fixed => quote!(&_rt::RedactFixed),
+			Redact::Length => quote!(&_rt::RedactLength(#var_name)),
+			Redact::Last(count) => quote!(&_rt::RedactLast(#var_name, #count)),
+			Redact::With(path) => quote!(&_rt::FormatWith(#var_name, #path)),
+		}
+	}
+}
@@ -1,12 +1,39 @@
 use syn::parse::{Parse, ParseStream};
 
+use crate::rename::RenameRule;
+
 #[derive(Clone, Debug, Default)]
 pub struct VariantAttribute {
-	pub format: Option<proc_macro2::TokenStream>,
+	pub keys: Vec<syn::Ident>,
+	pub rename: Option<syn::LitStr>,
+	pub rename_all: Option<RenameRule>,
+	pub pad: bool,
+	pub transparent: bool,
+	pub format: Option<super::format::Format>,
 }
 
-impl VariantAttribute {
-	pub fn update(&mut self, other: Self) {
+impl super::KeyValueAttribute for VariantAttribute {
+	const KEYS: &'static [&'static str] = &["format", "pad", "rename", "rename_all", "transparent"];
+	const EXCLUSIVE: &'static [&'static [&'static str]] = &[&["format", "transparent"], &["pad", "transparent"]];
+
+	fn keys(&self) -> &[syn::Ident] {
+		&self.keys
+	}
+
+	fn update(&mut self, other: Self) {
+		self.keys.extend(other.keys);
+		if other.rename.is_some() {
+			self.rename = other.rename;
+		}
+		if other.rename_all.is_some() {
+			self.rename_all = other.rename_all;
+		}
+		if other.pad {
+			self.pad = true;
+		}
+		if other.transparent {
+			self.transparent = true;
+		}
 		if other.format.is_some() {
 			self.format = other.format;
 		}
@@ -16,13 +43,41 @@ impl VariantAttribute {
 impl Parse for VariantAttribute {
 	fn parse(input: ParseStream) -> syn::Result<Self> {
 		let mut result = Self::default();
-		let lookahead = input.lookahead1();
 
-		if !input.is_empty() {
-			if lookahead.peek(syn::LitStr) {
-				result.format = Some(input.parse()?);
-			} else {
-				return Err(lookahead.error());
+		while !input.is_empty() {
+			if input.peek(syn::LitStr) {
+				let format: super::format::Format = input.parse()?;
+				format.reject_self_fields()?;
+				result.keys.push(syn::Ident::new("format", format.string.span()));
+				result.format = Some(format);
+				break;
+			}
+
+			let key = super::parse_key(input, <Self as super::KeyValueAttribute>::KEYS)?;
+			result.keys.push(key.clone());
+			match key.to_string().as_str() {
+				"format" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					let format: super::format::Format = input.parse()?;
+					format.reject_self_fields()?;
+					result.format = Some(format);
+					break;
+				}
+				"rename" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.rename = Some(input.parse()?);
+				}
+				"rename_all" => {
+					let _eq: syn::Token![=] = input.parse()?;
+					result.rename_all = Some(input.parse()?);
+				}
+				"pad" => result.pad = true,
+				"transparent" => result.transparent = true,
+				_ => unreachable!("`parse_key` only accepts the given keys"),
+			}
+
+			if !input.is_empty() {
+				let _comma: syn::Token![,] = input.parse()?;
 			}
 		}
 
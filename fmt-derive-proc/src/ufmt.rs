@@ -0,0 +1,114 @@
+use quote::{format_ident, quote};
+
+use crate::layout::{TextDerive, derive_text};
+use crate::syntax::format::Format;
+use crate::syntax::placeholder::Placeholder;
+
+/// One of the formatting traits from `ufmt` that can be derived.
+#[derive(Clone, Copy, Debug)]
+pub struct UfmtTrait {
+	/// The name of the trait in `ufmt`.
+	pub name: &'static str,
+	/// The attributes that configure the derived implementation of this trait, in ascending order of precedence. Its
+	/// output resembles that of the corresponding trait from `core::fmt`, so the attributes of that trait apply as well.
+	pub attributes: &'static [&'static str],
+}
+
+impl UfmtTrait {
+	pub const UDEBUG: UfmtTrait = UfmtTrait {
+		name: "uDebug",
+		attributes: &["fmt", "debug", "udebug"],
+	};
+	pub const UDISPLAY: UfmtTrait = UfmtTrait {
+		name: "uDisplay",
+		attributes: &["fmt", "display", "udisplay"],
+	};
+
+	/// The runtime type that prints a value using this trait if possible and a replacement string otherwise, e.g.,
+	/// `UDebugOrReplacement`.
+	fn or_replacement(&self) -> syn::Ident {
+		format_ident!("U{}OrReplacement", &self.name[1..])
+	}
+}
+
+pub fn ufmt(
+	item: proc_macro::TokenStream,
+	use_rt: &proc_macro2::TokenStream,
+	ufmt_trait: &UfmtTrait,
+) -> proc_macro::TokenStream {
+	derive_text(item, use_rt, ufmt_trait)
+}
+
+/// Writes the output with `ufmt::uwrite!` and `ufmt::Formatter::write_str`, propagating the errors of the writer.
+impl TextDerive for UfmtTrait {
+	/// The statements that write the value of the field.
+	type Field = proc_macro2::TokenStream;
+
+	fn name(&self) -> &'static str {
+		"ufmt"
+	}
+
+	fn attributes(&self) -> &'static [&'static str] {
+		self.attributes
+	}
+
+	fn trait_name(&self) -> &'static str {
+		self.name
+	}
+
+	/// The path of the trait, e.g., `ufmt::uDebug`.
+	fn trait_path(&self) -> proc_macro2::TokenStream {
+		let ident = syn::Ident::new(self.name, proc_macro2::Span::call_site());
+		quote!(ufmt::#ident)
+	}
+
+	fn signature(&self) -> proc_macro2::TokenStream {
+		quote!(
+			fn fmt<FmtDeriveWriter>(
+				&self,
+				fmt_derive_formatter_variable: &mut ufmt::Formatter<'_, FmtDeriveWriter>,
+			) -> ::core::result::Result<(), <FmtDeriveWriter as ufmt::uWrite>::Error>
+			where
+				FmtDeriveWriter: ufmt::uWrite + ?Sized,
+		)
+	}
+
+	fn text(&self, text: &str) -> proc_macro2::TokenStream {
+		self.write_str(text)
+	}
+
+	fn format(&self, format: &Format, scope: &syn::Fields) -> syn::Result<proc_macro2::TokenStream> {
+		let (format, arguments) = format.ufmt(scope)?;
+		let format = syn::LitStr::new(&format, proc_macro2::Span::call_site());
+		Ok(quote!(ufmt::uwrite!(fmt_derive_formatter_variable, #format #(, #arguments)*)?;))
+	}
+
+	fn placeholder(
+		&self,
+		placeholder: &Placeholder,
+		ty: &syn::Type,
+		var_name: &syn::Ident,
+	) -> syn::Result<proc_macro2::TokenStream> {
+		let placeholder = placeholder.expand_ufmt(ty)?;
+		let or_replacement = self.or_replacement();
+		Ok(quote!(
+			_rt::#or_replacement::<#ty>(#var_name).ufmt(#placeholder, fmt_derive_formatter_variable)?;
+		))
+	}
+
+	fn write_field(&self, prefix: &str, field: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		let prefix = self.write_str(prefix);
+		quote!(#prefix #field)
+	}
+
+	fn write_str(&self, text: &str) -> proc_macro2::TokenStream {
+		match text {
+			"" => quote!(),
+			text => quote!(fmt_derive_formatter_variable.write_str(#text)?;),
+		}
+	}
+
+	fn finish(&self, statements: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+		quote!({ #statements ::core::result::Result::Ok(()) })
+	}
+}
@@ -4,22 +4,61 @@
 //! The whole of this module is semver version exempt, as it is not intended to be used directly (think of it as an
 //! unexposed internal dependency).
 
+pub use crate::JsonDisplay;
+pub use crate::visit::{Inspect, Visitor};
+
 pub trait Replacement {
-	fn tuple_field<'a, 'b, 'c>(
-		&self,
-		replacement: &str,
-		w: &'a mut core::fmt::DebugTuple<'b, 'c>,
-	) -> &'a mut core::fmt::DebugTuple<'b, 'c> {
-		w.field(&DebugDisplay(replacement))
+	fn forward(&self, replacement: &dyn core::fmt::Display, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "{}", replacement)
 	}
 
-	fn struct_field<'a, 'b, 'c>(
+	fn tuple_field<'a, W: TupleBuilder>(&self, replacement: &dyn core::fmt::Display, w: &'a mut W) -> &'a mut W {
+		w.add_field(&DebugDisplay(replacement));
+		w
+	}
+
+	fn struct_field<'a, W: StructBuilder>(
 		&self,
 		name: &str,
-		replacement: &str,
-		w: &'a mut core::fmt::DebugStruct<'b, 'c>,
-	) -> &'a mut core::fmt::DebugStruct<'b, 'c> {
-		w.field(name, &DebugDisplay(replacement))
+		replacement: &dyn core::fmt::Display,
+		w: &'a mut W,
+	) -> &'a mut W {
+		w.add_field(name, &DebugDisplay(replacement));
+		w
+	}
+
+	fn inspect(&self, fallback: impl FnOnce(&mut dyn Visitor), visitor: &mut dyn Visitor) {
+		fallback(visitor);
+	}
+
+	fn inspect_debug(&self, replacement: &dyn core::fmt::Display, visitor: &mut dyn Visitor) {
+		visitor.visit_placeholder(replacement);
+	}
+
+	fn fmt_json(
+		&self,
+		fallback: impl FnOnce(&mut core::fmt::Formatter) -> core::fmt::Result,
+		f: &mut core::fmt::Formatter,
+	) -> core::fmt::Result {
+		fallback(f)
+	}
+
+	fn fmt_json_debug(&self, replacement: &dyn core::fmt::Display, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "{}", JsonString(replacement))
+	}
+
+	#[cfg(feature = "defmt")]
+	fn defmt(&self, replacement: impl FnOnce() -> DefmtPlaceholder) -> DefmtPlaceholder {
+		replacement()
+	}
+
+	#[cfg(feature = "ufmt")]
+	fn ufmt<W: ufmt::uWrite + ?Sized>(
+		&self,
+		replacement: UfmtPlaceholder,
+		f: &mut ufmt::Formatter<'_, W>,
+	) -> Result<(), W::Error> {
+		ufmt::uDisplay::fmt(&replacement, f)
 	}
 }
 impl<T> Replacement for T {}
@@ -27,45 +66,314 @@ impl<T> Replacement for T {}
 pub struct DebugOrReplacement<'a, T>(pub &'a T);
 
 impl<T: core::fmt::Debug> DebugOrReplacement<'_, T> {
-	pub fn tuple_field<'b, 'c, 'd>(
-		&self,
-		_replacement: &str,
-		w: &'b mut core::fmt::DebugTuple<'c, 'd>,
-	) -> &'b mut core::fmt::DebugTuple<'c, 'd> {
-		w.field(&self.0)
+	pub fn forward(&self, _replacement: &dyn core::fmt::Display, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		core::fmt::Debug::fmt(self.0, f)
+	}
+
+	pub fn tuple_field<'b, W: TupleBuilder>(&self, _replacement: &dyn core::fmt::Display, w: &'b mut W) -> &'b mut W {
+		w.add_field(&self.0);
+		w
 	}
 
-	pub fn struct_field<'b, 'c, 'd>(
+	pub fn struct_field<'b, W: StructBuilder>(
 		&self,
 		name: &str,
-		_replacement: &str,
-		w: &'b mut core::fmt::DebugStruct<'c, 'd>,
-	) -> &'b mut core::fmt::DebugStruct<'c, 'd> {
-		w.field(name, &self.0)
+		_replacement: &dyn core::fmt::Display,
+		w: &'b mut W,
+	) -> &'b mut W {
+		w.add_field(name, &self.0);
+		w
+	}
+
+	pub fn inspect_debug(&self, _replacement: &dyn core::fmt::Display, visitor: &mut dyn Visitor) {
+		visitor.visit_debug(self.0);
+	}
+
+	pub fn fmt_json_debug(
+		&self,
+		_replacement: &dyn core::fmt::Display,
+		f: &mut core::fmt::Formatter,
+	) -> core::fmt::Result {
+		write!(f, "{}", JsonString(&format_args!("{:?}", self.0)))
+	}
+}
+
+/// Inspects a value if its type implements [`Inspect`], or calls the fallback otherwise, which usually tries
+/// [`DebugOrReplacement::inspect_debug`] next.
+pub struct InspectOrReplacement<'a, T>(pub &'a T);
+
+impl<T: Inspect> InspectOrReplacement<'_, T> {
+	pub fn inspect(&self, _fallback: impl FnOnce(&mut dyn Visitor), visitor: &mut dyn Visitor) {
+		self.0.inspect(visitor);
+	}
+}
+
+/// Formats a value as JSON if its type implements [`JsonDisplay`], or calls the fallback otherwise, which usually tries
+/// [`DebugOrReplacement::fmt_json_debug`] next.
+pub struct JsonOrReplacement<'a, T>(pub &'a T);
+
+impl<T: JsonDisplay> JsonOrReplacement<'_, T> {
+	pub fn fmt_json(
+		&self,
+		_fallback: impl FnOnce(&mut core::fmt::Formatter) -> core::fmt::Result,
+		f: &mut core::fmt::Formatter,
+	) -> core::fmt::Result {
+		self.0.fmt_json(f)
+	}
+}
+
+/// Prints the `Display` output of a value as a JSON string, i.e., quoted and escaped.
+pub struct JsonString<'a, T: core::fmt::Display + ?Sized>(pub &'a T);
+
+impl<T: core::fmt::Display + ?Sized> core::fmt::Display for JsonString<'_, T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		use core::fmt::Write;
+
+		/// Escapes everything that is written to the inner formatter.
+		struct Escape<'a, 'b>(&'a mut core::fmt::Formatter<'b>);
+
+		impl core::fmt::Write for Escape<'_, '_> {
+			fn write_str(&mut self, s: &str) -> core::fmt::Result {
+				let mut unescaped = 0;
+				for (index, c) in s.char_indices() {
+					if c != '"' && c != '\\' && !c.is_ascii_control() {
+						continue;
+					}
+					self.0.write_str(&s[unescaped..index])?;
+					match c {
+						'"' => self.0.write_str("\\\"")?,
+						'\\' => self.0.write_str("\\\\")?,
+						'\n' => self.0.write_str("\\n")?,
+						'\r' => self.0.write_str("\\r")?,
+						'\t' => self.0.write_str("\\t")?,
+						_ => write!(self.0, "\\u{:04x}", c as u32)?,
+					}
+					unescaped = index + c.len_utf8();
+				}
+				self.0.write_str(&s[unescaped..])
+			}
+		}
+
+		f.write_char('"')?;
+		write!(Escape(f), "{}", self.0)?;
+		f.write_char('"')
+	}
+}
+
+/// Writes the fields of a JSON object or the elements of a JSON array, similar to
+/// [`DebugStruct`](core::fmt::DebugStruct) and [`DebugTuple`](core::fmt::DebugTuple).
+pub struct JsonFields<'a, 'b> {
+	f: &'a mut core::fmt::Formatter<'b>,
+	result: core::fmt::Result,
+	empty: bool,
+	end: char,
+}
+
+impl<'a, 'b> JsonFields<'a, 'b> {
+	pub fn object(f: &'a mut core::fmt::Formatter<'b>) -> Self {
+		let result = f.write_str("{");
+		JsonFields {
+			f,
+			result,
+			empty: true,
+			end: '}',
+		}
+	}
+
+	pub fn array(f: &'a mut core::fmt::Formatter<'b>) -> Self {
+		let result = f.write_str("[");
+		JsonFields {
+			f,
+			result,
+			empty: true,
+			end: ']',
+		}
+	}
+
+	/// Adds a field to an object.
+	pub fn field(&mut self, name: &str, value: impl FnOnce(&mut core::fmt::Formatter) -> core::fmt::Result) -> &mut Self {
+		self.element(|f| {
+			write!(f, "{}:", JsonString(name))?;
+			value(f)
+		})
+	}
+
+	/// Adds an element to an array.
+	pub fn element(&mut self, value: impl FnOnce(&mut core::fmt::Formatter) -> core::fmt::Result) -> &mut Self {
+		self.result = self.result.and_then(|()| {
+			if !self.empty {
+				self.f.write_str(",")?;
+			}
+			value(self.f)
+		});
+		self.empty = false;
+		self
+	}
+
+	pub fn finish(&mut self) -> core::fmt::Result {
+		use core::fmt::Write;
+
+		self.result.and_then(|()| self.f.write_char(self.end))
 	}
 }
 
 pub struct DisplayOrReplacement<'a, T>(pub &'a T);
 
 impl<T: core::fmt::Display> DisplayOrReplacement<'_, T> {
-	pub fn tuple_field<'b, 'c, 'd>(
-		&self,
-		_replacement: &str,
-		w: &'b mut core::fmt::DebugTuple<'c, 'd>,
-	) -> &'b mut core::fmt::DebugTuple<'c, 'd> {
-		w.field(&DebugDisplay(&self.0))
+	pub fn forward(&self, _replacement: &dyn core::fmt::Display, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		core::fmt::Display::fmt(self.0, f)
 	}
 
-	pub fn struct_field<'b, 'c, 'd>(
+	pub fn tuple_field<'b, W: TupleBuilder>(&self, _replacement: &dyn core::fmt::Display, w: &'b mut W) -> &'b mut W {
+		w.add_field(&DebugDisplay(&self.0));
+		w
+	}
+
+	pub fn struct_field<'b, W: StructBuilder>(
 		&self,
 		name: &str,
-		_replacement: &str,
-		w: &'b mut core::fmt::DebugStruct<'c, 'd>,
-	) -> &'b mut core::fmt::DebugStruct<'c, 'd> {
-		w.field(name, &DebugDisplay(&self.0))
+		_replacement: &dyn core::fmt::Display,
+		w: &'b mut W,
+	) -> &'b mut W {
+		w.add_field(name, &DebugDisplay(&self.0));
+		w
+	}
+}
+
+/// The placeholder given by `placeholder(type_name)`.
+pub struct TypeName(pub &'static str);
+
+impl core::fmt::Display for TypeName {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "<{}>", self.0)
 	}
 }
 
+/// The placeholder given by `placeholder(with = path)`, which calls the function with the name of the type.
+pub struct PlaceholderWith(
+	pub &'static str,
+	pub fn(&str, &mut core::fmt::Formatter) -> core::fmt::Result,
+);
+
+impl core::fmt::Display for PlaceholderWith {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		(self.1)(self.0, f)
+	}
+}
+
+#[cfg(feature = "defmt")]
+pub struct DefmtOrReplacement<'a, T>(pub &'a T);
+
+#[cfg(feature = "defmt")]
+impl<'a, T: defmt::Format> DefmtOrReplacement<'a, T> {
+	pub fn defmt(&self, _replacement: impl FnOnce() -> DefmtPlaceholder) -> &'a T {
+		self.0
+	}
+}
+
+/// The placeholder printed by `defmt` for a field whose type does not implement `defmt::Format`.
+#[cfg(feature = "defmt")]
+pub enum DefmtPlaceholder {
+	/// A fixed text, which is interned.
+	Text(defmt::Str),
+	/// The placeholder given by `placeholder(type_name)`.
+	TypeName(&'static str),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DefmtPlaceholder {
+	fn format(&self, fmt: defmt::Formatter) {
+		match self {
+			DefmtPlaceholder::Text(text) => defmt::write!(fmt, "{=istr}", *text),
+			DefmtPlaceholder::TypeName(type_name) => defmt::write!(fmt, "<{=str}>", type_name),
+		}
+	}
+}
+
+#[cfg(feature = "ufmt")]
+pub struct UDebugOrReplacement<'a, T>(pub &'a T);
+
+#[cfg(feature = "ufmt")]
+impl<T: ufmt::uDebug> UDebugOrReplacement<'_, T> {
+	pub fn ufmt<W: ufmt::uWrite + ?Sized>(
+		&self,
+		_replacement: UfmtPlaceholder,
+		f: &mut ufmt::Formatter<'_, W>,
+	) -> Result<(), W::Error> {
+		ufmt::uDebug::fmt(self.0, f)
+	}
+}
+
+#[cfg(feature = "ufmt")]
+pub struct UDisplayOrReplacement<'a, T>(pub &'a T);
+
+#[cfg(feature = "ufmt")]
+impl<T: ufmt::uDisplay> UDisplayOrReplacement<'_, T> {
+	pub fn ufmt<W: ufmt::uWrite + ?Sized>(
+		&self,
+		_replacement: UfmtPlaceholder,
+		f: &mut ufmt::Formatter<'_, W>,
+	) -> Result<(), W::Error> {
+		ufmt::uDisplay::fmt(self.0, f)
+	}
+}
+
+/// The placeholder printed by `ufmt` for a field whose type does not implement the derived trait.
+#[cfg(feature = "ufmt")]
+pub enum UfmtPlaceholder {
+	/// A fixed text.
+	Text(&'static str),
+	/// The placeholder given by `placeholder(type_name)`.
+	TypeName(&'static str),
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for UfmtPlaceholder {
+	fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+		match self {
+			UfmtPlaceholder::Text(text) => f.write_str(text),
+			UfmtPlaceholder::TypeName(type_name) => {
+				f.write_str("<")?;
+				f.write_str(type_name)?;
+				f.write_str(">")
+			}
+		}
+	}
+}
+
+/// Copies the integer behind any number of references, as `ufmt` formats only the integers themselves in hexadecimal.
+#[cfg(feature = "ufmt")]
+pub trait UfmtHex {
+	type Integer;
+
+	fn ufmt_hex(&self) -> Self::Integer;
+}
+
+#[cfg(feature = "ufmt")]
+impl<T: UfmtHex + ?Sized> UfmtHex for &T {
+	type Integer = T::Integer;
+
+	fn ufmt_hex(&self) -> Self::Integer {
+		(**self).ufmt_hex()
+	}
+}
+
+#[cfg(feature = "ufmt")]
+macro_rules! ufmt_hex {
+	($($integer:ty)*) => {$(
+		impl UfmtHex for $integer {
+			type Integer = $integer;
+
+			fn ufmt_hex(&self) -> Self::Integer {
+				*self
+			}
+		}
+	)*};
+}
+
+#[cfg(feature = "ufmt")]
+ufmt_hex! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
 pub struct DebugDisplay<'a, T: core::fmt::Display + ?Sized>(pub &'a T);
 
 impl<T: core::fmt::Display + ?Sized> core::fmt::Debug for DebugDisplay<'_, T> {
@@ -73,3 +381,568 @@ impl<T: core::fmt::Display + ?Sized> core::fmt::Debug for DebugDisplay<'_, T> {
 		write!(f, "{}", self.0)
 	}
 }
+
+/// Defines a `...OrReplacement` type and a `Debug...` adapter (analogous to [`DisplayOrReplacement`] and
+/// [`DebugDisplay`]) for each of the remaining formatting traits.
+macro_rules! or_replacement {
+	($($fmt_trait:ident: $or_replacement:ident, $adapter:ident;)*) => {$(
+		pub struct $or_replacement<'a, T>(pub &'a T);
+
+		impl<T: core::fmt::$fmt_trait> $or_replacement<'_, T> {
+			pub fn forward(&self, _replacement: &dyn core::fmt::Display, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				core::fmt::$fmt_trait::fmt(self.0, f)
+			}
+
+			pub fn tuple_field<'b, W: TupleBuilder>(&self, _replacement: &dyn core::fmt::Display, w: &'b mut W) -> &'b mut W {
+				w.add_field(&$adapter(self.0));
+				w
+			}
+
+			pub fn struct_field<'b, W: StructBuilder>(
+				&self,
+				name: &str,
+				_replacement: &dyn core::fmt::Display,
+				w: &'b mut W,
+			) -> &'b mut W {
+				w.add_field(name, &$adapter(self.0));
+				w
+			}
+		}
+
+		pub struct $adapter<'a, T: core::fmt::$fmt_trait + ?Sized>(pub &'a T);
+
+		impl<T: core::fmt::$fmt_trait + ?Sized> core::fmt::Debug for $adapter<'_, T> {
+			fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				core::fmt::$fmt_trait::fmt(self.0, f)
+			}
+		}
+	)*};
+}
+
+or_replacement! {
+	LowerHex: LowerHexOrReplacement, DebugLowerHex;
+	UpperHex: UpperHexOrReplacement, DebugUpperHex;
+	Binary: BinaryOrReplacement, DebugBinary;
+	Octal: OctalOrReplacement, DebugOctal;
+	LowerExp: LowerExpOrReplacement, DebugLowerExp;
+	UpperExp: UpperExpOrReplacement, DebugUpperExp;
+	Pointer: PointerOrReplacement, DebugPointer;
+}
+
+/// Receives the fields of a `struct`, as implemented by [`DebugStruct`](core::fmt::DebugStruct) and [`StructLayout`].
+pub trait StructBuilder {
+	fn add_field(&mut self, name: &str, value: &dyn core::fmt::Debug);
+}
+
+impl StructBuilder for core::fmt::DebugStruct<'_, '_> {
+	fn add_field(&mut self, name: &str, value: &dyn core::fmt::Debug) {
+		self.field(name, value);
+	}
+}
+
+/// Receives the fields of a tuple, as implemented by [`DebugTuple`](core::fmt::DebugTuple) and [`TupleLayout`].
+pub trait TupleBuilder {
+	fn add_field(&mut self, value: &dyn core::fmt::Debug);
+}
+
+impl TupleBuilder for core::fmt::DebugTuple<'_, '_> {
+	fn add_field(&mut self, value: &dyn core::fmt::Debug) {
+		self.field(value);
+	}
+}
+
+/// The layouts given by `layout = "..."`, apart from `"debug"`, which uses the builders of [`core::fmt::Formatter`].
+#[derive(Clone, Copy)]
+pub enum Layout {
+	/// `a=1 b=2`
+	Fields,
+	/// `1, 2`
+	Values,
+	/// `a=1 b="two words"`, quoting and escaping values as required by logfmt.
+	Logfmt,
+}
+
+/// Prints the fields of a `struct` in one of the [`Layout`]s, with the same interface as
+/// [`DebugStruct`](core::fmt::DebugStruct).
+pub struct StructLayout<'a, 'b> {
+	f: &'a mut core::fmt::Formatter<'b>,
+	layout: Layout,
+	result: core::fmt::Result,
+	has_fields: bool,
+}
+
+impl<'a, 'b> StructLayout<'a, 'b> {
+	pub fn new(f: &'a mut core::fmt::Formatter<'b>, layout: Layout) -> Self {
+		StructLayout {
+			f,
+			layout,
+			result: Ok(()),
+			has_fields: false,
+		}
+	}
+
+	pub fn field(&mut self, name: &str, value: &dyn core::fmt::Debug) -> &mut Self {
+		self.entry(&name, value);
+		self
+	}
+
+	pub fn finish(&mut self) -> core::fmt::Result {
+		self.result
+	}
+
+	/// Ends the fields with `..`, except for [`Layout::Logfmt`], which has no notation for omitted fields.
+	pub fn finish_non_exhaustive(&mut self) -> core::fmt::Result {
+		if self.result.is_ok() && !matches!(self.layout, Layout::Logfmt) {
+			self.result = self.separator().and_then(|()| self.f.write_str(".."));
+		}
+		self.result
+	}
+
+	fn separator(&mut self) -> core::fmt::Result {
+		if !self.has_fields {
+			return Ok(());
+		}
+		self.f.write_str(match self.layout {
+			Layout::Values => ", ",
+			Layout::Fields | Layout::Logfmt => " ",
+		})
+	}
+
+	fn entry(&mut self, name: &dyn core::fmt::Display, value: &dyn core::fmt::Debug) {
+		if self.result.is_err() {
+			return;
+		}
+		self.result = (|| {
+			self.separator()?;
+			match self.layout {
+				Layout::Fields => write!(self.f, "{}={:?}", name, value),
+				Layout::Values => write!(self.f, "{:?}", value),
+				Layout::Logfmt => {
+					write!(self.f, "{}=", name)?;
+					write_logfmt_value(self.f, value)
+				}
+			}
+		})();
+		self.has_fields = true;
+	}
+}
+
+impl StructBuilder for StructLayout<'_, '_> {
+	fn add_field(&mut self, name: &str, value: &dyn core::fmt::Debug) {
+		self.field(name, value);
+	}
+}
+
+/// Prints the fields of a tuple in one of the [`Layout`]s, using their indices as names, with the same interface as
+/// [`DebugTuple`](core::fmt::DebugTuple).
+pub struct TupleLayout<'a, 'b> {
+	inner: StructLayout<'a, 'b>,
+	index: usize,
+}
+
+impl<'a, 'b> TupleLayout<'a, 'b> {
+	pub fn new(f: &'a mut core::fmt::Formatter<'b>, layout: Layout) -> Self {
+		TupleLayout {
+			inner: StructLayout::new(f, layout),
+			index: 0,
+		}
+	}
+
+	pub fn field(&mut self, value: &dyn core::fmt::Debug) -> &mut Self {
+		self.inner.entry(&self.index, value);
+		self.index += 1;
+		self
+	}
+
+	pub fn finish(&mut self) -> core::fmt::Result {
+		self.inner.finish()
+	}
+
+	pub fn finish_non_exhaustive(&mut self) -> core::fmt::Result {
+		self.inner.finish_non_exhaustive()
+	}
+}
+
+impl TupleBuilder for TupleLayout<'_, '_> {
+	fn add_field(&mut self, value: &dyn core::fmt::Debug) {
+		self.field(value);
+	}
+}
+
+/// Writes `value` as a logfmt value, which is quoted (and escaped) if it is empty or contains spaces, `=`, `"` or
+/// control characters.
+fn write_logfmt_value(f: &mut core::fmt::Formatter, value: &dyn core::fmt::Debug) -> core::fmt::Result {
+	struct NeedsQuotes {
+		empty: bool,
+		needs_quotes: bool,
+	}
+
+	impl core::fmt::Write for NeedsQuotes {
+		fn write_str(&mut self, s: &str) -> core::fmt::Result {
+			self.empty &= s.is_empty();
+			self.needs_quotes |= s.chars().any(|c| c == ' ' || c == '=' || c == '"' || c.is_control());
+			Ok(())
+		}
+	}
+
+	struct Escaping<'a, 'b>(&'a mut core::fmt::Formatter<'b>);
+
+	impl core::fmt::Write for Escaping<'_, '_> {
+		fn write_str(&mut self, s: &str) -> core::fmt::Result {
+			for c in s.chars() {
+				match c {
+					'"' => self.0.write_str("\\\""),
+					'\\' => self.0.write_str("\\\\"),
+					'\n' => self.0.write_str("\\n"),
+					'\r' => self.0.write_str("\\r"),
+					'\t' => self.0.write_str("\\t"),
+					c => core::fmt::Write::write_char(self.0, c),
+				}?;
+			}
+			Ok(())
+		}
+	}
+
+	let mut check = NeedsQuotes {
+		empty: true,
+		needs_quotes: false,
+	};
+	core::fmt::write(&mut check, format_args!("{:?}", value))?;
+	if check.empty || check.needs_quotes {
+		f.write_str("\"")?;
+		core::fmt::write(&mut Escaping(f), format_args!("{:?}", value))?;
+		f.write_str("\"")
+	} else {
+		write!(f, "{:?}", value)
+	}
+}
+
+/// Prints a value using a user-provided function, as given by `with = path` or `redact(with = path)`.
+pub struct FormatWith<'a, T: ?Sized>(pub &'a T, pub fn(&T, &mut core::fmt::Formatter) -> core::fmt::Result);
+
+impl<T: ?Sized> core::fmt::Debug for FormatWith<'_, T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		(self.1)(self.0, f)
+	}
+}
+
+/// Prints a fixed mask instead of the redacted value.
+pub struct RedactFixed;
+
+impl core::fmt::Debug for RedactFixed {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str("***")
+	}
+}
+
+/// Prints one `*` for every character of the [`Display`](core::fmt::Display) representation of the redacted value.
+pub struct RedactLength<'a, T: core::fmt::Display + ?Sized>(pub &'a T);
+
+impl<T: core::fmt::Display + ?Sized> core::fmt::Debug for RedactLength<'_, T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let length = count_chars(self.0)?;
+		MaskingWriter { f, masked: length }.write_mask()
+	}
+}
+
+/// Prints the last `.1` characters of the [`Display`](core::fmt::Display) representation of the redacted value and
+/// masks all other characters with `*`.
+pub struct RedactLast<'a, T: core::fmt::Display + ?Sized>(pub &'a T, pub usize);
+
+impl<T: core::fmt::Display + ?Sized> core::fmt::Debug for RedactLast<'_, T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let length = count_chars(self.0)?;
+		let mut writer = MaskingWriter {
+			f,
+			masked: length.saturating_sub(self.1),
+		};
+		core::fmt::Write::write_fmt(&mut writer, format_args!("{}", self.0))
+	}
+}
+
+/// Counts the characters of the [`Display`](core::fmt::Display) representation of `value` without allocating.
+fn count_chars<T: core::fmt::Display + ?Sized>(value: &T) -> Result<usize, core::fmt::Error> {
+	struct Counter(usize);
+
+	impl core::fmt::Write for Counter {
+		fn write_str(&mut self, s: &str) -> core::fmt::Result {
+			self.0 += s.chars().count();
+			Ok(())
+		}
+	}
+
+	let mut counter = Counter(0);
+	core::fmt::Write::write_fmt(&mut counter, format_args!("{}", value))?;
+	Ok(counter.0)
+}
+
+/// Forwards characters to the formatter, replacing the first `masked` of them with `*`.
+struct MaskingWriter<'a, 'b> {
+	f: &'a mut core::fmt::Formatter<'b>,
+	masked: usize,
+}
+
+impl MaskingWriter<'_, '_> {
+	fn write_mask(&mut self) -> core::fmt::Result {
+		for _ in 0..self.masked {
+			core::fmt::Write::write_char(self.f, '*')?;
+		}
+		self.masked = 0;
+		Ok(())
+	}
+}
+
+impl core::fmt::Write for MaskingWriter<'_, '_> {
+	fn write_str(&mut self, s: &str) -> core::fmt::Result {
+		for c in s.chars() {
+			if self.masked > 0 {
+				self.masked -= 1;
+				core::fmt::Write::write_char(self.f, '*')?;
+			} else {
+				core::fmt::Write::write_char(self.f, c)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Prints bytes as a list of hexadecimal numbers, e.g., `[0x01, 0xff]`, as given by `union_bytes`.
+pub struct HexBytes<'a>(pub &'a [u8]);
+
+impl core::fmt::Debug for HexBytes<'_> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.debug_list()
+			.entries(self.0.iter().map(|&byte| HexByte(byte)))
+			.finish()
+	}
+}
+
+struct HexByte(u8);
+
+impl core::fmt::Debug for HexByte {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "{:#04x}", self.0)
+	}
+}
+
+/// Types without padding, of which every byte is initialized in every value.
+///
+/// # Safety
+/// Implementing types must not have padding (or any other uninitialized bytes).
+#[diagnostic::on_unimplemented(
+	message = "`union_bytes` cannot prove that `{Self}` has no padding",
+	note = "only integers, floating point numbers, `bool`, `char` and arrays of them are known to have no padding"
+)]
+pub unsafe trait NoPadding {}
+
+macro_rules! no_padding {
+	($($ty:ty)*) => {$(
+		// SAFETY: primitive types have no padding
+		unsafe impl NoPadding for $ty {}
+	)*};
+}
+
+no_padding! { bool char f32 f64 i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+// SAFETY: the elements of an array are laid out without gaps between them
+unsafe impl<T: NoPadding, const N: usize> NoPadding for [T; N] {}
+
+/// Fails to compile (when evaluated in a `const` context) unless the field type `T` has no padding and is as large as
+/// the `union` `U`, which `union_bytes` requires of all fields, so that every byte of the union is initialized no
+/// matter which field it was created through.
+pub const fn assert_spans_union<T: NoPadding, U>() {
+	assert!(
+		core::mem::size_of::<T>() == core::mem::size_of::<U>(),
+		"`union_bytes` requires all fields to be as large as the union"
+	);
+}
+
+/// Applies the width, fill, alignment and precision of `f` to the whole output of `write`, like
+/// [`Formatter::pad`](core::fmt::Formatter::pad) does for a `str`, as given by `pad`. Instead of buffering the output,
+/// `write` is called twice: once to count the characters and once to actually write them.
+///
+/// The alternate and sign flags are passed on to `write`. Zero padding works like it does for numbers: the output is
+/// right aligned, and the zeros are inserted after a leading sign.
+pub fn pad<F: Fn(&mut core::fmt::Formatter) -> core::fmt::Result>(
+	f: &mut core::fmt::Formatter,
+	write: F,
+) -> core::fmt::Result {
+	if f.width().is_none() && f.precision().is_none() {
+		return write(f);
+	}
+
+	let output = Render(write);
+	let (alternate, sign_plus, sign_minus) = (f.alternate(), f.sign_plus(), f.sign_minus());
+	let render = |w: &mut dyn core::fmt::Write| match (alternate, sign_plus, sign_minus) {
+		(false, false, false) => write!(w, "{}", output),
+		(true, false, false) => write!(w, "{:#}", output),
+		(false, true, _) => write!(w, "{:+}", output),
+		(true, true, _) => write!(w, "{:+#}", output),
+		(false, false, true) => write!(w, "{:-}", output),
+		(true, false, true) => write!(w, "{:-#}", output),
+	};
+
+	let mut counter = CharCounter { count: 0, first: None };
+	render(&mut counter)?;
+	let shown = f
+		.precision()
+		.map_or(counter.count, |precision| counter.count.min(precision));
+	let padding = f.width().map_or(0, |width| width.saturating_sub(shown));
+	let (before, after) = match f.align() {
+		_ if f.sign_aware_zero_pad() => (padding, 0),
+		Some(core::fmt::Alignment::Right) => (padding, 0),
+		Some(core::fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+		Some(core::fmt::Alignment::Left) | None => (0, padding),
+	};
+	let (fill, sign) = match counter.first {
+		_ if !f.sign_aware_zero_pad() => (f.fill(), None),
+		Some(sign @ ('+' | '-')) if shown > 0 => ('0', Some(sign)),
+		_ => ('0', None),
+	};
+
+	let mut truncating = TruncatingWriter {
+		f,
+		skip: 0,
+		remaining: shown,
+	};
+	if let Some(sign) = sign {
+		core::fmt::Write::write_char(truncating.f, sign)?;
+		truncating.skip = 1;
+		truncating.remaining -= 1;
+	}
+	for _ in 0..before {
+		core::fmt::Write::write_char(truncating.f, fill)?;
+	}
+	render(&mut truncating)?;
+	for _ in 0..after {
+		core::fmt::Write::write_char(truncating.f, fill)?;
+	}
+	Ok(())
+}
+
+/// Displays the output of a formatting function.
+struct Render<F>(F);
+
+impl<F: Fn(&mut core::fmt::Formatter) -> core::fmt::Result> core::fmt::Display for Render<F> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		(self.0)(f)
+	}
+}
+
+/// Counts the characters written to it and remembers the first one.
+struct CharCounter {
+	count: usize,
+	first: Option<char>,
+}
+
+impl core::fmt::Write for CharCounter {
+	fn write_str(&mut self, s: &str) -> core::fmt::Result {
+		if self.count == 0 {
+			self.first = s.chars().next();
+		}
+		self.count += s.chars().count();
+		Ok(())
+	}
+}
+
+/// Drops the first `skip` characters, forwards the next `remaining` ones to the formatter and drops the rest.
+struct TruncatingWriter<'a, 'b> {
+	f: &'a mut core::fmt::Formatter<'b>,
+	skip: usize,
+	remaining: usize,
+}
+
+impl core::fmt::Write for TruncatingWriter<'_, '_> {
+	fn write_str(&mut self, mut s: &str) -> core::fmt::Result {
+		if self.skip > 0 {
+			match s.char_indices().nth(self.skip) {
+				Some((start, _)) => {
+					self.skip = 0;
+					s = &s[start..];
+				}
+				None => {
+					self.skip -= s.chars().count();
+					return Ok(());
+				}
+			}
+		}
+		match s.char_indices().nth(self.remaining) {
+			Some((end, _)) => {
+				self.remaining = 0;
+				self.f.write_str(&s[..end])
+			}
+			None => {
+				self.remaining -= s.chars().count();
+				self.f.write_str(s)
+			}
+		}
+	}
+}
+
+/// Converts an error source into a trait object, which also works for sources that are already trait objects (e.g.,
+/// `Box<dyn Error>`).
+pub trait AsDynError<'a> {
+	fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a);
+}
+
+impl<'a, T: core::error::Error + 'a> AsDynError<'a> for T {
+	fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+		self
+	}
+}
+
+impl<'a> AsDynError<'a> for dyn core::error::Error + 'a {
+	fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+		self
+	}
+}
+
+impl<'a> AsDynError<'a> for dyn core::error::Error + Send + 'a {
+	fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+		self
+	}
+}
+
+impl<'a> AsDynError<'a> for dyn core::error::Error + Send + Sync + 'a {
+	fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+		self
+	}
+}
+
+pub use crate::ParseError;
+
+/// A part of a format string that a derived `FromStr` implementation matches, see [`match_segments`].
+pub enum Segment {
+	/// Text that must occur as is.
+	Literal(&'static str),
+	/// Text that must be accepted by the parser with the given index.
+	Field(usize),
+}
+
+/// Matches `input` against `segments`, trying every possible split of the input between the fields until all literals
+/// match and every parser accepts its part of the input. Shorter parts are tried first, so that a field ends at the
+/// first occurrence of the following literal that leads to a successful match. Returns whether a match was found, in
+/// which case the last call of each parser was with its matching part. The derive never places two fields next to each
+/// other, as the split between them would be ambiguous. In the worst case, the parsers are called O(n^k) times for `k`
+/// fields and an input of `n` bytes, as every field may end at every position.
+pub fn match_segments(input: &str, segments: &[Segment], parsers: &mut [&mut dyn FnMut(&str) -> bool]) -> bool {
+	match segments.split_first() {
+		None => input.is_empty(),
+		Some((Segment::Literal(literal), rest)) => input
+			.strip_prefix(literal)
+			.is_some_and(|input| match_segments(input, rest, parsers)),
+		Some((Segment::Field(index), rest)) => {
+			for end in (0..=input.len()).filter(|end| input.is_char_boundary(*end)) {
+				let accepted = match rest.first() {
+					None => end == input.len(),
+					Some(Segment::Literal(literal)) => input[end..].starts_with(literal),
+					Some(Segment::Field(_)) => unreachable!("the derive rejects adjacent fields"),
+				};
+				if accepted && (parsers[*index])(&input[..end]) && match_segments(&input[end..], rest, parsers) {
+					return true;
+				}
+			}
+			false
+		}
+	}
+}
@@ -0,0 +1,161 @@
+use core::fmt::{Display, Formatter, Result};
+
+use crate::_rt::JsonString;
+
+/// Formats a value as JSON, as derived by [`fmt_derive::Json`](crate::Json). Use [`json`] to print it with `{}`.
+pub trait JsonDisplay {
+	/// Writes the value to `f` as compact JSON.
+	fn fmt_json(&self, f: &mut Formatter) -> Result;
+}
+
+/// Wraps a value so that its [`Display`] implementation prints it as JSON.
+///
+/// ```rust
+/// assert_eq!(fmt_derive::json(&[Some("a\"b"), None]).to_string(), r#"["a\"b",null]"#);
+/// ```
+pub fn json<T: JsonDisplay + ?Sized>(value: &T) -> AsJson<'_, T> {
+	AsJson(value)
+}
+
+/// A value that is printed as JSON, as returned by [`json`].
+#[derive(Clone, Copy)]
+pub struct AsJson<'a, T: JsonDisplay + ?Sized>(pub &'a T);
+
+impl<T: JsonDisplay + ?Sized> Display for AsJson<'_, T> {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		self.0.fmt_json(f)
+	}
+}
+
+impl<T: JsonDisplay + ?Sized> JsonDisplay for &T {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		(**self).fmt_json(f)
+	}
+}
+
+impl<T: JsonDisplay + ?Sized> JsonDisplay for &mut T {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		(**self).fmt_json(f)
+	}
+}
+
+impl<T: JsonDisplay> JsonDisplay for Option<T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		match self {
+			Some(value) => value.fmt_json(f),
+			None => f.write_str("null"),
+		}
+	}
+}
+
+impl<T: JsonDisplay> JsonDisplay for [T] {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		let mut array = crate::_rt::JsonFields::array(f);
+		for element in self {
+			array.element(|f| element.fmt_json(f));
+		}
+		array.finish()
+	}
+}
+
+impl<T: JsonDisplay, const N: usize> JsonDisplay for [T; N] {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		self.as_slice().fmt_json(f)
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T: JsonDisplay> JsonDisplay for alloc::vec::Vec<T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		self.as_slice().fmt_json(f)
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T: JsonDisplay> JsonDisplay for alloc::collections::VecDeque<T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		let mut array = crate::_rt::JsonFields::array(f);
+		for element in self {
+			array.element(|f| element.fmt_json(f));
+		}
+		array.finish()
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T: JsonDisplay + ?Sized> JsonDisplay for alloc::boxed::Box<T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		(**self).fmt_json(f)
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T: JsonDisplay + ?Sized> JsonDisplay for alloc::rc::Rc<T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		(**self).fmt_json(f)
+	}
+}
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+impl<T: JsonDisplay + ?Sized> JsonDisplay for alloc::sync::Arc<T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		(**self).fmt_json(f)
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<T: JsonDisplay + alloc::borrow::ToOwned + ?Sized> JsonDisplay for alloc::borrow::Cow<'_, T> {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		(**self).fmt_json(f)
+	}
+}
+
+impl JsonDisplay for str {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		write!(f, "{}", JsonString(self))
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl JsonDisplay for alloc::string::String {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		self.as_str().fmt_json(f)
+	}
+}
+
+impl JsonDisplay for char {
+	fn fmt_json(&self, f: &mut Formatter) -> Result {
+		write!(f, "{}", JsonString(self))
+	}
+}
+
+/// Implements [`JsonDisplay`] for types whose `Display` output is a valid JSON literal. The literal is written without
+/// the options of the formatter, which could turn it into invalid JSON (e.g., `{:+}`).
+macro_rules! json_literal {
+	($($ty:ty)*) => {$(
+		impl JsonDisplay for $ty {
+			fn fmt_json(&self, f: &mut Formatter) -> Result {
+				write!(f, "{}", self)
+			}
+		}
+	)*};
+}
+
+json_literal! { bool i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+/// Implements [`JsonDisplay`] for floating point types, which JSON cannot represent if they are not finite.
+macro_rules! json_float {
+	($($ty:ty)*) => {$(
+		impl JsonDisplay for $ty {
+			fn fmt_json(&self, f: &mut Formatter) -> Result {
+				if self.is_finite() {
+					write!(f, "{}", self)
+				} else {
+					f.write_str("null")
+				}
+			}
+		}
+	)*};
+}
+
+json_float! { f32 f64 }
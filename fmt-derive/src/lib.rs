@@ -120,6 +120,87 @@
 //! }
 //! ```
 //!
+//! ## Referring to Fields in Format Strings
+//! Placeholders in format strings may name fields directly: `{x}` refers to the field `x`, `{0}` to the first tuple
+//! field (unless explicit positional arguments are given), and dotted paths such as `{inner.name}` or `{self.x}` access
+//! nested fields. Widths and precisions refer to fields the same way, e.g., `{0:>1$}` pads the first tuple field to the
+//! width given by the second one:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Display;
+//!
+//! struct Location {
+//!   file: &'static str,
+//! }
+//!
+//! #[derive(Display)]
+//! enum Error {
+//!   #[display("unexpected {0:?} in line {1}")]
+//!   Unexpected(char, u32),
+//!   #[display("cannot open {location.file}: {reason}")]
+//!   Open { location: Location, reason: &'static str },
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{}", Error::Unexpected('x', 7)), "unexpected 'x' in line 7");
+//!   assert_eq!(
+//!     format!("{}", Error::Open { location: Location { file: "a.txt" }, reason: "not found" }),
+//!     "cannot open a.txt: not found",
+//!   );
+//! }
+//! ```
+//!
+//! A name that does not refer to a field is captured from the surrounding scope, just like `format_args!` does:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Display;
+//!
+//! const MAX: u32 = 10;
+//!
+//! #[derive(Display)]
+//! #[display("{level}/{MAX}")]
+//! struct Volume {
+//!   level: u32,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{}", Volume { level: 3 }), "3/10");
+//! }
+//! ```
+//!
+//! In the format of a variant, `self` is the whole `enum`, so its fields must be named directly instead of through
+//! `self`:
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! // error: `{self.code}` cannot be used in the format of a variant, as `self` is the whole `enum`
+//! #[derive(fmt_derive::Display)]
+//! enum Status {
+//!   #[display("failed with {self.code}")]
+//!   Failed { code: i32 },
+//! }
+//! ```
+//!
+//! Format strings are checked when the derive is expanded: malformed placeholders, unknown format traits, references to
+//! positional arguments that do not exist and arguments that are never used are reported at the offending attribute.
+//! On nightly, the error points at the offending placeholder itself. Stable Rust cannot point into a string literal, so
+//! the error points at the whole format string there, and the message quotes the placeholder instead:
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! // error: `{}` refers to positional argument 1, but there is only 1 argument
+//! #[derive(fmt_derive::Display)]
+//! #[display("{} of {}", self.done)]
+//! struct Progress {
+//!   done: u32,
+//!   total: u32,
+//! }
+//! ```
+//!
 //! ## Custom Format Expressions for Individual Fields
 //! Or by customizing an individual field:
 //!
@@ -135,6 +216,31 @@
 //! }
 //! ```
 //!
+//! ## Custom Formatting Functions for Individual Fields
+//! Formatting logic that is shared between many fields can be moved into a function with the signature
+//! `fn(&T, &mut Formatter) -> fmt::Result`, which is then referenced with `with = path`:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! fn hex(value: &u32, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+//!   write!(f, "{:#x}", value)
+//! }
+//!
+//! #[derive(Debug)]
+//! struct Registers {
+//!   #[debug(with = hex)]
+//!   a: u32,
+//!   #[debug(with = hex)]
+//!   b: u32,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Registers { a: 255, b: 16 }), "Registers { a: 0xff, b: 0x10 }");
+//! }
+//! ```
+//!
 //! ## Ignoring a Field
 //! Although it is possible to derive a debug message for any field, it is sometimes preferable to not print a field at
 //! all:
@@ -150,21 +256,822 @@
 //!   assert_eq!(format!("{:?}", Function(main)), "Function");
 //! }
 //! ```
+//!
+//! ## Skipping a Field Conditionally
+//! `skip_if = path` omits a field at runtime whenever the given function `fn(&T) -> bool` returns `true` for it, which
+//! keeps the output of large structures with mostly empty fields readable:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! struct Config {
+//!   #[debug(skip_if = Option::is_none)]
+//!   name: Option<&'static str>,
+//!   #[debug(skip_if = Vec::is_empty)]
+//!   tags: Vec<&'static str>,
+//!   retries: u32,
+//! }
+//!
+//! fn main() {
+//!   let config = Config { name: None, tags: vec!["fast"], retries: 3 };
+//!   assert_eq!(format!("{:?}", config), r#"Config { tags: ["fast"], retries: 3 }"#);
+//! }
+//! ```
+//!
+//! ## Marking Omitted Fields
+//! By default, the output does not show that fields were left out, just like the derives of `std`. With
+//! `non_exhaustive`, the fields always end with `..`, and with `non_exhaustive = "auto"`, they do so whenever a field
+//! is ignored or has `skip_if` (even if it is printed at runtime) or the `struct` or variant is marked
+//! `#[non_exhaustive]`. As `layout = "logfmt"` has no notation for omitted fields, `"auto"` never adds `..` to it and
+//! `non_exhaustive` (i.e., `"always"`) cannot be combined with it:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! #[debug(non_exhaustive = "auto")]
+//! struct Config {
+//!   a: u32,
+//!   #[debug(ignore)]
+//!   b: u32,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Config { a: 1, b: 2 }), "Config { a: 1, .. }");
+//! }
+//! ```
+//!
+//! ## Transparent Newtypes
+//! With `transparent`, a `struct` or variant is formatted exactly like its only field that is not ignored. Unlike a
+//! format string such as `"{}"`, this passes on the formatter, so that width, precision and all other options apply to
+//! the field. If the field is omitted by `skip_if`, the output is empty, but still padded to the width. Given on an
+//! enumeration, `transparent` applies to all of its variants:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Debug, Display};
+//!
+//! #[derive(Debug, Display)]
+//! #[fmt(transparent)]
+//! struct UserId(u64);
+//!
+//! fn main() {
+//!   assert_eq!(format!("[{:>4}]", UserId(42)), "[  42]");
+//!   assert_eq!(format!("{:?}", UserId(42)), "42");
+//! }
+//! ```
+//!
+//! Using `transparent` with more than one printed field is an error:
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! // error: `transparent` requires exactly one field that is not ignored, but there are 2
+//! #[derive(fmt_derive::Display)]
+//! #[display(transparent)]
+//! struct Point(i32, i32);
+//! ```
+//!
+//! ## Padding
+//! A format string creates new formatting arguments, so the width, fill, alignment and precision given by the caller
+//! (e.g., `{:>10}`) are ignored by default. With `pad` on an item or variant, they are applied to the whole output like
+//! they would be for a `str`, which helps to line up derived types in tables. This works without allocating, but the
+//! output is produced twice: once to measure it and once to write it. The alternate flag (`#`) and the sign flags (`+`
+//! and `-`) are passed on to the output as well, and zero padding (e.g., `{:08}`) inserts the zeros after a leading
+//! sign, like it does for numbers.
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Display;
+//!
+//! #[derive(Display)]
+//! #[display(pad, "{x}/{y}")]
+//! struct Point {
+//!   x: i32,
+//!   y: i32,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("[{:>8}]", Point { x: 1, y: -2 }), "[    1/-2]");
+//! }
+//! ```
+//!
+//! ## Redacting a Field
+//! Secrets can be hidden without removing the field from the output. `redact` prints a fixed mask, `redact(length)`
+//! masks every character of the `Display` representation, `redact(last = N)` reveals only the last `N` characters of
+//! it, and `redact(with = path)` calls a function `fn(&T, &mut Formatter) -> fmt::Result` instead:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! struct Login {
+//!   user: &'static str,
+//!   #[debug(redact)]
+//!   password: &'static str,
+//!   #[debug(redact(last = 4))]
+//!   card: &'static str,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(
+//!     format!("{:?}", Login { user: "admin", password: "hunter2", card: "1234123412341234" }),
+//!     r#"Login { user: "admin", password: ***, card: ************1234 }"#,
+//!   );
+//! }
+//! ```
+//!
+//! ## Renaming
+//! The names of types, variants and fields can be changed with `rename = "..."`, while `rename_all = "..."` applies a
+//! case conversion to all fields (for structures) or all variants and their fields (for enumerations). Supported rules
+//! are `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` and
+//! `SCREAMING-KEBAB-CASE`. An explicit `rename` always takes precedence over a `rename_all` rule.
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! #[debug(rename = "request", rename_all = "camelCase")]
+//! struct Request {
+//!   user_id: u32,
+//!   #[debug(rename = "kind")]
+//!   r#type: u32,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Request { user_id: 1, r#type: 2 }), "request { userId: 1, kind: 2 }");
+//! }
+//! ```
+//!
+//! ## Fieldless Enumerations
+//! `Display` prints unit variants (and unit structures) as their plain name, which makes deriving `Display` for
+//! fieldless enumerations a replacement for hand-written `match` statements. Combined with `rename_all`, the names can
+//! follow any of the supported case conventions. Like a `str`, the name respects the width, fill, alignment and
+//! precision given to the formatter:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Display;
+//!
+//! #[derive(Display)]
+//! #[display(rename_all = "kebab-case")]
+//! enum Color {
+//!   Red,
+//!   DarkGreen,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{}", Color::DarkGreen), "dark-green");
+//!   assert_eq!(format!("[{:>5}]", Color::Red), "[  red]");
+//! }
+//! ```
+//!
+//! ## Layouts
+//! Without a format string, the fields of a `struct` or variant are laid out like `Debug` does (`Name { a: 1, b: 2 }`).
+//! The item-level `layout = "..."` selects a different layout, which only prints the fields themselves:
+//!
+//! - `"debug"` (the default): `Name { a: 1, b: 2 }` or `Name(1, 2)`
+//! - `"fields"`: `a=1 b=2`, where tuple fields are named by their index
+//! - `"values"`: `1, 2`
+//! - `"logfmt"`: like `"fields"`, but values that are empty or contain spaces, `=`, `"` or control characters are
+//!   quoted and escaped, e.g., `a=1 b="two words"`
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Display;
+//!
+//! #[derive(Display)]
+//! #[display(layout = "logfmt")]
+//! struct Request {
+//!   method: &'static str,
+//!   path: &'static str,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{}", Request { method: "GET", path: "/a b" }), r#"method=GET path="/a b""#);
+//! }
+//! ```
+//!
+//! ## Tagged Unions
+//! Unions are printed as `<Name>` by default, as it is unknown which of their fields is active. If the active field
+//! can be determined, `unsafe(variant = ...)` names a method (which is called with `self`) or an expression that
+//! returns the name of the active field as a `&str`. The union is then printed as if it were a `struct` with just that
+//! field, while a name that matches no field prints `<Name>`. As the derived implementation reads whichever field is
+//! named, the selector must only ever name a field that is initialized, which `unsafe` acknowledges (just like an
+//! `unsafe` block would):
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Clone, Copy, Debug)]
+//! #[repr(C)]
+//! struct Key {
+//!   kind: u32,
+//!   code: u32,
+//! }
+//!
+//! #[derive(Debug)]
+//! #[debug(unsafe(variant = Self::active))]
+//! #[repr(C)]
+//! union Event {
+//!   kind: u32,
+//!   key: Key,
+//! }
+//!
+//! impl Event {
+//!   fn active(&self) -> &'static str {
+//!     // SAFETY: all fields start with `kind`
+//!     match unsafe { self.kind } {
+//!       1 => "key",
+//!       _ => "kind",
+//!     }
+//!   }
+//! }
+//!
+//! fn main() {
+//!   let event = Event { key: Key { kind: 1, code: 65 } };
+//!   assert_eq!(format!("{:?}", event), "Event { key: Key { kind: 1, code: 65 } }");
+//!   assert_eq!(format!("{:?}", Event { kind: 2 }), "Event { kind: 2 }");
+//! }
+//! ```
+//!
+//! If the active field cannot be determined, `unsafe(union_bytes)` prints the memory of the union as a list of bytes
+//! instead. Reading every byte of the union is only sound if all of them are initialized, so every field must be as
+//! large as the union and be free of padding, which the derive checks at compile time: only integers, floating point
+//! numbers, `bool`, `char` and arrays of them are accepted as the types of fields.
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! #[debug(unsafe(union_bytes))]
+//! union Word {
+//!   bytes: [u8; 2],
+//!   value: u16,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Word { bytes: [0x12, 0xAB] }), "Word { bytes: [0x12, 0xab] }");
+//! }
+//! ```
+//!
+//! A union created through a smaller field has uninitialized bytes, so it is rejected:
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! #[debug(unsafe(union_bytes))]
+//! union Number {
+//!   small: u8,
+//!   large: u32,
+//! }
+//! #
+//! # fn main() {
+//! #   println!("{:?}", Number { small: 1 });
+//! # }
+//! ```
+//!
+//! ## Placeholders
+//! A field whose type does not implement the derived trait is printed as its type in angle brackets, e.g.,
+//! `<Vec<foo::Foo>>`. `placeholder(...)` on an item or field chooses a different text: `placeholder(short)` leaves out
+//! all module paths (`<Vec<Foo>>`), `placeholder(type_name)` uses [`core::any::type_name`] at runtime,
+//! `placeholder(text = "...")` prints a fixed text and `placeholder(with = path)` calls a function
+//! `fn(&str, &mut Formatter) -> fmt::Result` with the result of `type_name`. For a whole crate, `placeholder` in the
+//! `[package.metadata.fmt-derive]` table of its `Cargo.toml` takes the same argument as a string and applies to all of
+//! its items that do not give a placeholder themselves, without affecting any other crate. Cargo does not notice when
+//! the table is added to a manifest that did not have one, so that a crate has to be rebuilt (e.g., by touching one of
+//! its source files) for it to take effect:
+//!
+//! ```toml
+//! [package.metadata.fmt-derive]
+//! placeholder = "short"
+//! ```
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Debug;
+//!
+//! mod foo {
+//!   pub struct Foo;
+//! }
+//!
+//! #[derive(Debug)]
+//! #[debug(placeholder(short))]
+//! struct Short(Vec<foo::Foo>, #[debug(placeholder(text = "?"))] foo::Foo);
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Short(vec![], foo::Foo)), "Short(<Vec<Foo>>, ?)");
+//! }
+//! ```
+//!
+//! ## Strict Mode
+//! Falling back to a placeholder keeps the derives compiling no matter what, but sometimes a field was meant to be
+//! printable. With `strict` on an item, every field that would be printed as a placeholder is a compile error naming
+//! the field and its type, and with `strict = "warn"`, it is a deprecation warning instead. `strict = "allow"` on an
+//! item or field restores the default. For a whole crate, `strict` in the `[package.metadata.fmt-derive]` table of its
+//! `Cargo.toml` (see [Placeholders](#placeholders)) sets the default to `"allow"`, `"warn"` or `"deny"` for all of its
+//! items that do not give a strictness themselves, without affecting any other crate. This is deliberately not a Cargo
+//! feature: Cargo enables a feature for every crate in the build once any of them asks for it, so a `strict` feature
+//! enabled by one crate would break the build of every other crate that relies on placeholders.
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! struct Unprintable;
+//!
+//! // error: field `unprintable` of type `Unprintable` does not implement `Debug`
+//! #[derive(fmt_derive::Debug)]
+//! #[debug(strict)]
+//! struct Strict {
+//!   unprintable: Unprintable,
+//! }
+//! ```
+//!
+//! ```rust,compile_fail
+//! #![deny(deprecated)]
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! struct Unprintable;
+//!
+//! // warning: use of deprecated method: field `0` of type `Unprintable` does not implement `Debug` and is printed as a
+//! // placeholder
+//! #[derive(fmt_derive::Debug)]
+//! #[debug(strict = "warn")]
+//! struct Warn(Unprintable);
+//! ```
+//!
+//! ## Combining Attributes
+//! Attributes consist of comma separated keys such as `ignore`, `rename = "..."` or `format = "..."`, where the format
+//! string (with its arguments) must come last and may also be given without the `format =` prefix. The shared
+//! `#[fmt(...)]` applies to both `Debug` and `Display`, while keys given in `#[debug(...)]` or `#[display(...)]` take
+//! precedence over the same keys given in `#[fmt(...)]`. As `ignore`, `redact`, `with` and the format string all decide
+//! how a field is printed, giving any of them in `#[debug(...)]` overrides all of them from `#[fmt(...)]`:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Debug, Display};
+//!
+//! #[derive(Debug, Display)]
+//! struct Token {
+//!   #[fmt(ignore)]
+//!   #[debug(redact)]
+//!   secret: u64,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Token { secret: 42 }), "Token { secret: *** }");
+//!   assert_eq!(format!("{}", Token { secret: 42 }), "Token");
+//! }
+//! ```
+//!
+//! Unknown keys, keys that are given more than once for the same attribute name and contradictory keys are errors:
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! // error: `format` contradicts `ignore` in `#[debug(...)]`
+//! #[derive(fmt_derive::Debug)]
+//! struct Token {
+//!   #[debug(ignore, "{:x}", self.secret)]
+//!   secret: u64,
+//! }
+//! ```
+//!
+//! # Generics
+//! By default, no bounds are added to the derived implementation, so that it is available for every instantiation of a
+//! generic type. However, a field of type `T` is then always printed as `<T>`, even if the concrete `T` is printable:
+//! whether a field implements the derived trait is decided where the generic type is defined, and choosing between the
+//! actual value and the placeholder per instantiation would require specialization, which stable Rust does not offer.
+//!
+//! With `infer_bounds`, a bound such as `T: Debug` is added for every type parameter that occurs in a printed field
+//! (just like `derive(std::fmt::Debug)` does), so that these fields are printed properly. Bounds can also be specified
+//! explicitly with `bound = "..."`, either for the whole item (replacing all inferred bounds) or for a single field
+//! (replacing its inferred bounds):
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use core::marker::PhantomData;
+//!
+//! use fmt_derive::Debug;
+//!
+//! #[derive(Debug)]
+//! struct Plain<T>(T);
+//!
+//! #[derive(Debug)]
+//! #[debug(infer_bounds)]
+//! struct Inferred<T, U> {
+//!   value: T,
+//!   #[debug(bound = "")]
+//!   marker: PhantomData<U>,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:?}", Plain(42)), "Plain(<T>)");
+//!   assert_eq!(
+//!     format!("{:?}", Inferred::<u32, u8> { value: 42, marker: PhantomData }),
+//!     "Inferred { value: 42, marker: PhantomData<u8> }",
+//!   );
+//! }
+//! ```
+//!
+//! # Other Formatting Traits
+//! The remaining formatting traits from [`core::fmt`] ([`LowerHex`], [`UpperHex`], [`Binary`], [`Octal`],
+//! [`LowerExp`], [`UpperExp`] and [`Pointer`]) can be derived as well. Each of them is configured with attributes of
+//! the same name in `snake_case` (e.g., `#[lower_hex(...)]`), and `use`ing its derive macro also pulls in the trait of
+//! the same name, just like [`fmt_derive::Debug`](Debug) does. As these traits are usually derived for newtypes, a
+//! `struct` or variant with exactly one printed field is formatted exactly like that field, passing on all formatting
+//! options. Anything else is structured like `Display` (with each field formatted using the derived trait), and fields
+//! that do not implement the trait are printed as their placeholder:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Binary, LowerHex};
+//!
+//! #[derive(LowerHex, Binary)]
+//! struct Register(u16);
+//!
+//! #[derive(LowerHex)]
+//! enum Access {
+//!   Read(u8),
+//!   Write { address: u8, value: u8 },
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(format!("{:#06x}", Register(0xBEE)), "0x0bee");
+//!   assert_eq!(format!("{:b}", Register(5)), "101");
+//!   assert_eq!(format!("{:02x}", Access::Read(10)), "0a");
+//!   assert_eq!(format!("{:x}", Access::Write { address: 16, value: 255 }), "Write { address: 10, value: ff }");
+//! }
+//! ```
+//!
+//! # Errors
+//! [`fmt_derive::Error`](Error) derives `Display` exactly like [`fmt_derive::Display`](Display) and additionally
+//! implements [`core::error::Error`]. The field marked with `#[error(source)]` is returned from
+//! [`source`](core::error::Error::source), while `#[error(from)]` also derives a [`From`] implementation for a field
+//! that is the only one in its `struct` or variant:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Debug, Error};
+//!
+//! #[derive(Debug, Error)]
+//! #[display("disk full")]
+//! struct DiskFull;
+//!
+//! #[derive(Debug, Error)]
+//! enum SaveError {
+//!   #[display("cannot save {name}")]
+//!   Io { name: &'static str, #[error(source)] cause: DiskFull },
+//!   #[display("out of space")]
+//!   Space(#[error(from)] DiskFull),
+//! }
+//!
+//! fn main() {
+//!   let error = SaveError::Io { name: "a.txt", cause: DiskFull };
+//!   assert_eq!(format!("{}", error), "cannot save a.txt");
+//!   assert_eq!(format!("{}", error.source().unwrap()), "disk full");
+//!
+//!   let error: SaveError = DiskFull.into();
+//!   assert_eq!(format!("{}", error), "out of space");
+//! }
+//! ```
+//!
+//! # Parsing
+//! [`fmt_derive::FromStr`](FromStr) derives parsers for the output of `Display` of `struct`s and variants whose output
+//! is given by a format string (`#[display("...")]` or `#[fmt("...")]`): the literal text of the format must match
+//! exactly, while every field it refers to is parsed with its own [`FromStr`](core::str::FromStr) implementation.
+//! Fields that the format does not refer to are set to their [`Default`]. Unit `struct`s and variants parse their
+//! (renamed) name, transparent ones their only printed field, and the variants of an `enum` are tried in order:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Display, FromStr};
+//!
+//! #[derive(Debug, Display, FromStr, PartialEq)]
+//! #[display(rename_all = "lowercase")]
+//! enum Interval {
+//!   #[display("every {0}s")]
+//!   Seconds(u32),
+//!   #[display("{from}..{to}")]
+//!   Range { from: u32, to: u32 },
+//!   Never,
+//! }
+//!
+//! fn main() {
+//!   assert_eq!("every 30s".parse(), Ok(Interval::Seconds(30)));
+//!   assert_eq!("1..5".parse(), Ok(Interval::Range { from: 1, to: 5 }));
+//!   assert_eq!(Interval::Never.to_string().parse(), Ok(Interval::Never));
+//!   assert!("every minute".parse::<Interval>().is_err());
+//! }
+//! ```
+//!
+//! Each field ends at the first occurrence of the following literal text that lets the remaining input match, so
+//! parsing is not always the inverse of `Display`: if a field can contain the text that follows it, a different value
+//! may be parsed from the output. Matching may try every position in the input for every field, so parsing `k` fields
+//! from an input of length `n` calls their parsers up to `n^k` times:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Display, FromStr};
+//!
+//! #[derive(Debug, Display, FromStr, PartialEq)]
+//! #[display("{a}-{b}")]
+//! struct Pair {
+//!   a: String,
+//!   b: String,
+//! }
+//!
+//! fn main() {
+//!   let pair = Pair { a: "x-y".into(), b: "z".into() };
+//!   assert_eq!(pair.to_string(), "x-y-z");
+//!   assert_eq!(pair.to_string().parse(), Ok(Pair { a: "x".into(), b: "y-z".into() }));
+//! }
+//! ```
+//!
+//! Format strings may only refer to each field once, must not use format specifications and must separate fields by
+//! literal text, since their output could not be parsed back reliably otherwise:
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! #[derive(fmt_derive::Display, fmt_derive::FromStr)]
+//! #[display("{0:>8}")]
+//! struct Padded(u32);
+//! ```
+//!
+//! ```rust,compile_fail
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! # fn main() {}
+//! // error: `{1}` cannot be parsed, as it directly follows another field, whose end would be ambiguous
+//! #[derive(fmt_derive::Display, fmt_derive::FromStr)]
+//! #[display("{0}{1}")]
+//! struct Version(u32, u32);
+//! ```
+//!
+//! # Inspecting Fields
+//! [`fmt_derive::Inspect`](Inspect) derives [`visit::Inspect`], which reports the structure of a value to a
+//! [`visit::Visitor`] instead of printing it, e.g., to emit structured logs without parsing the output of `Debug`. It
+//! is configured by `#[fmt(...)]`, `#[debug(...)]` and `#[inspect(...)]`, in ascending precedence, and visits exactly
+//! the fields that `Debug` prints, under the same (renamed) names. Fields whose type implements `visit::Inspect`,
+//! including the primitive types, are inspected themselves, while fields that are printed by a format string, `with`
+//! or `redact` are visited with their output. Fields of any other type are visited with their `Debug` output or, if
+//! they do not implement `Debug` either, as their placeholder. Options that only concern the text of the output, such
+//! as `layout`, `pad` and `non_exhaustive`, do not apply:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::Inspect;
+//! use fmt_derive::visit::{Inspect as _, Visitor};
+//!
+//! #[derive(Inspect)]
+//! #[inspect(rename_all = "snake_case")]
+//! struct Request {
+//!   path: &'static str,
+//!   status: u16,
+//!   #[inspect(redact)]
+//!   token: &'static str,
+//! }
+//!
+//! /// Collects the top-level fields as `key=value` pairs.
+//! #[derive(Default)]
+//! struct Pairs {
+//!   pairs: Vec<String>,
+//!   depth: usize,
+//! }
+//!
+//! impl Visitor for Pairs {
+//!   fn enter_struct(&mut self, _name: &str) { self.depth += 1; }
+//!   fn exit_struct(&mut self) { self.depth -= 1; }
+//!   fn enter_tuple(&mut self, _name: &str) { self.depth += 1; }
+//!   fn exit_tuple(&mut self) { self.depth -= 1; }
+//!   fn enter_field(&mut self, name: &str) {
+//!     if self.depth == 1 {
+//!       self.pairs.push(format!("{}=", name));
+//!     }
+//!   }
+//!   fn exit_field(&mut self) {}
+//!   fn visit_unit(&mut self, name: &str) { self.visit_str(name); }
+//!   fn visit_debug(&mut self, value: &dyn core::fmt::Debug) {
+//!     self.pairs.last_mut().unwrap().push_str(&format!("{:?}", value));
+//!   }
+//!   fn visit_str(&mut self, value: &str) {
+//!     self.pairs.last_mut().unwrap().push_str(value);
+//!   }
+//! }
+//!
+//! fn main() {
+//!   let request = Request { path: "/index", status: 200, token: "hunter2" };
+//!   let mut pairs = Pairs::default();
+//!   request.inspect(&mut pairs);
+//!   assert_eq!(pairs.pairs, ["path=/index", "status=200", "token=***"]);
+//! }
+//! ```
+//!
+//! # JSON
+//! [`fmt_derive::Json`](Json) derives [`JsonDisplay`], which renders a value as compact JSON without depending on
+//! `serde`, e.g., for log lines on `no_std` targets. Wrap a value in [`json`] to print it with `{}`. It is configured
+//! by `#[fmt(...)]`, `#[debug(...)]` and `#[json(...)]`, in ascending precedence, and renders exactly the fields that
+//! `Debug` prints, under the same (renamed) names. `struct`s with named fields become objects, tuple `struct`s become
+//! arrays and unit `struct`s become strings of their name. Variants with fields are wrapped in an object with the name
+//! of the variant as its only key. Fields whose type implements `JsonDisplay` (including numbers, `bool`s, strings,
+//! `Option`s, slices and arrays, and with the default `alloc` feature `String`s, `Vec`s, `Box`es and `Cow`s of them)
+//! are rendered natively, while fields that are printed by a format string, `with` or
+//! `redact` become strings of their output. Fields of any other type become strings of their `Debug` output or, if
+//! they do not implement `Debug` either, of their placeholder:
+//!
+//! ```rust
+//! # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+//! use fmt_derive::{Json, json};
+//!
+//! #[derive(Json)]
+//! #[json(rename_all = "camelCase")]
+//! struct Response {
+//!   status_code: u16,
+//!   message: &'static str,
+//!   #[json(redact)]
+//!   session: u64,
+//!   elapsed: Option<f32>,
+//! }
+//!
+//! fn main() {
+//!   let response = Response { status_code: 404, message: "no \"index\"", session: 7, elapsed: None };
+//!   assert_eq!(
+//!     json(&response).to_string(),
+//!     r#"{"statusCode":404,"message":"no \"index\"","session":"***","elapsed":null}"#,
+//!   );
+//! }
+//! ```
+//!
+//! # defmt
+//! With the `defmt` feature enabled, `fmt_derive::Defmt` derives `defmt::Format` for logging on embedded
+//! targets. The crate that uses the derive needs to depend on [`defmt`](https://docs.rs/defmt) itself. The output is
+//! structured like `Debug` and is configured by `#[fmt(...)]`, `#[debug(...)]` and `#[defmt(...)]`, in ascending
+//! precedence, so a type deriving both prints the same in either case. Format strings are translated for `defmt`,
+//! which only supports the alternate flag, zero padding and the formats `x`, `X`, `o` and `b`. Fields that do not
+//! implement `defmt::Format` are printed as their placeholder. Neither `with`, redaction strategies other than the
+//! plain `redact`, `placeholder(with = ...)` nor `layout = "logfmt"` are supported, and `pad` is ignored.
+//!
+//! # ufmt
+//! With the `ufmt` feature enabled, `fmt_derive::UDebug` and `fmt_derive::UDisplay` derive `ufmt::uDebug` and
+//! `ufmt::uDisplay` for targets on which the machinery of `core::fmt` is too large. The crate that uses the derives
+//! needs to depend on [`ufmt`](https://docs.rs/ufmt) itself. Their output matches that of `Debug` and `Display`, which
+//! also share their attributes: `UDebug` is configured by `#[fmt(...)]`, `#[debug(...)]` and `#[udebug(...)]`, and
+//! `UDisplay` by `#[fmt(...)]`, `#[display(...)]` and `#[udisplay(...)]`, in ascending precedence. Format strings may
+//! only use `{}`, `{:?}`, `{:#?}` and the formats `x` and `X` with the alternate flag, zero padding and a width, as
+//! `ufmt::uwrite!` does. Fields that do not implement the derived trait are printed as their placeholder. The same
+//! options as with `defmt` are unsupported or ignored, and the output always fits on a single line.
 
+/// Derive implementations of [`core::fmt::Binary`], configured by `#[binary(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::Binary;
 /// Derive implementations of `Debug` for arbitrary `struct`s and `enum`s (`union`s are supported only with a
-/// top-level format directive). `use`ing [`fmt_derive::Debug`](crate::Debug), will also pull in the
-/// [`core::fmt::Debug`]/[`std::fmt::Debug`] trait (but the macro of the same name will be replaced with this one).
+/// top-level format directive, `unsafe(variant = ...)` or `unsafe(union_bytes)`). `use`ing
+/// [`fmt_derive::Debug`](crate::Debug), will also pull in the [`core::fmt::Debug`]/[`std::fmt::Debug`] trait (but the
+/// macro of the same name will be replaced with this one).
 pub use fmt_derive_proc::Debug;
+/// Derive implementations of `defmt::Format` for arbitrary `struct`s and `enum`s, which print like `Debug`. Use
+/// `#[defmt(...)]` to customize the implementation beyond the attributes shared with `Debug`. Requires the `defmt`
+/// feature (see [the crate documentation](crate#defmt)).
+///
+/// ```rust
+/// # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+/// use fmt_derive::{Debug, Defmt};
+///
+/// #[derive(Debug, Defmt)]
+/// struct Frame {
+///   #[fmt("{:#06x}", self.id)]
+///   id: u16,
+///   #[fmt(ignore)]
+///   checksum: u32,
+///   payload: [u8; 4],
+/// }
+/// #
+/// # fn main() {
+/// #   let frame = Frame { id: 0x2a, checksum: 0, payload: [1, 2, 3, 4] };
+/// #   assert_eq!(format!("{:?}", frame), "Frame { id: 0x002a, payload: [1, 2, 3, 4] }");
+/// # }
+/// ```
+///
+/// Format strings that `defmt` cannot reproduce are rejected:
+///
+/// ```rust,compile_fail
+/// # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+/// # fn main() {}
+/// #[derive(fmt_derive::Defmt)]
+/// #[fmt("{:>8}", self.0)]
+/// struct Aligned(u32);
+/// ```
+#[cfg(feature = "defmt")]
+pub use fmt_derive_proc::Defmt;
 /// Derive implementations of `Display` for arbitrary `struct`s and `enum`s (`union`s are supported only with a
-/// top-level format directive).. `use`ing [`fmt_derive::Display`](crate::Display), will also pull in the
-/// [`core::fmt::Display`]/[`std::fmt::Display`] trait (but the macro of the same name will be replaced with this
-/// one).
+/// top-level format directive, `unsafe(variant = ...)` or `unsafe(union_bytes)`). `use`ing
+/// [`fmt_derive::Display`](crate::Display), will also pull in the [`core::fmt::Display`]/[`std::fmt::Display`] trait
+/// (but the macro of the same name will be replaced with this one).
 pub use fmt_derive_proc::Display;
+/// Derive implementations of `Display` and [`core::error::Error`] for arbitrary `struct`s and `enum`s. The message is
+/// derived exactly as with [`fmt_derive::Display`](crate::Display), while fields marked with `#[error(source)]` are
+/// returned from [`source`](core::error::Error::source). Marking the only field of a `struct` or variant with
+/// `#[error(from)]` additionally derives a [`From`] implementation for it. `use`ing
+/// [`fmt_derive::Error`](crate::Error), will also pull in the [`core::error::Error`]/[`std::error::Error`] trait.
+pub use fmt_derive_proc::Error;
+/// Derive implementations of [`FromStr`](core::str::FromStr) for `struct`s and `enum`s whose `Display` output is given
+/// by format strings (`#[display("...")]` or `#[fmt("...")]`), parsing the output of those formats again. Unit structs
+/// and variants parse their (renamed) name and transparent ones their only field. Variants are tried in order.
+/// `use`ing [`fmt_derive::FromStr`](crate::FromStr), will also pull in the [`core::str::FromStr`]/[`std::str::FromStr`]
+/// trait.
+pub use fmt_derive_proc::FromStr;
+/// Derive implementations of [`visit::Inspect`] for arbitrary `struct`s and `enum`s, which report the fields that
+/// `Debug` would print to a [`visit::Visitor`]. Use `#[inspect(...)]` to customize the implementation beyond the
+/// attributes shared with `Debug` (see [the crate documentation](crate#inspecting-fields)).
+pub use fmt_derive_proc::Inspect;
+/// Derive implementations of [`JsonDisplay`] for arbitrary `struct`s and `enum`s, which render the fields that `Debug`
+/// would print as JSON. Use `#[json(...)]` to customize the implementation beyond the attributes shared with `Debug`
+/// (see [the crate documentation](crate#json)).
+pub use fmt_derive_proc::Json;
+/// Derive implementations of [`core::fmt::LowerExp`], configured by `#[lower_exp(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::LowerExp;
+/// Derive implementations of [`core::fmt::LowerHex`], configured by `#[lower_hex(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::LowerHex;
+/// Derive implementations of [`core::fmt::Octal`], configured by `#[octal(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::Octal;
+/// Derive implementations of [`core::fmt::Pointer`], configured by `#[pointer(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::Pointer;
+/// Derive implementations of `ufmt::uDebug` for arbitrary `struct`s and `enum`s, which print like `Debug` (on a single
+/// line, even with `{:#?}`). Use `#[udebug(...)]` to customize the implementation beyond the attributes shared with
+/// `Debug`. Requires the `ufmt` feature (see [the crate documentation](crate#ufmt)).
+///
+/// ```rust
+/// # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+/// use fmt_derive::{Debug, UDebug};
+///
+/// #[derive(Debug, UDebug)]
+/// struct Frame {
+///   #[fmt("{:#06x}", self.id)]
+///   id: u16,
+///   #[fmt(ignore)]
+///   checksum: u32,
+///   payload: [u8; 4],
+/// }
+///
+/// fn main() {
+///   let frame = Frame { id: 0x2a, checksum: 0, payload: [1, 2, 3, 4] };
+///   let mut output = String::new();
+///   ufmt::uwrite!(output, "{:?}", frame).unwrap();
+///   assert_eq!(output, "Frame { id: 0x002a, payload: [1, 2, 3, 4] }");
+///   assert_eq!(output, format!("{:?}", frame));
+/// }
+/// ```
+///
+/// Format strings that `ufmt` cannot reproduce are rejected:
+///
+/// ```rust,compile_fail
+/// # use fmt_derive::_rt; // required for doctests in the `fmt_derive` crate only
+/// # fn main() {}
+/// #[derive(fmt_derive::UDebug)]
+/// #[fmt("{:>8}", self.0)]
+/// struct Aligned(u32);
+/// ```
+#[cfg(feature = "ufmt")]
+pub use fmt_derive_proc::UDebug;
+/// Derive implementations of `ufmt::uDisplay` for arbitrary `struct`s and `enum`s, which print like `Display`. Use
+/// `#[udisplay(...)]` to customize the implementation beyond the attributes shared with `Display`. Requires the `ufmt`
+/// feature (see [the crate documentation](crate#ufmt)).
+#[cfg(feature = "ufmt")]
+pub use fmt_derive_proc::UDisplay;
+/// Derive implementations of [`core::fmt::UpperExp`], configured by `#[upper_exp(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::UpperExp;
+/// Derive implementations of [`core::fmt::UpperHex`], configured by `#[upper_hex(...)]` (see [the crate
+/// documentation](crate#other-formatting-traits)).
+pub use fmt_derive_proc::UpperHex;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 pub mod _rt;
 
+pub mod visit;
+
+mod json;
+mod parse_error;
 mod test;
 
+pub use json::{AsJson, JsonDisplay, json};
+pub use parse_error::ParseError;
+
 /// Glob-exporting this module reexports original [`core::fmt::Debug`] *trait*, while shadowing the macro of the same
 /// name (due to the specific reexport of [`fmt_derive_proc::Debug`]). This enables the following use case:
 ///
@@ -207,9 +1114,27 @@ mod test;
 /// }
 /// ```
 mod shadowed_reexport_trick {
+	/// The trait from the standard library.
+	pub use core::error::Error;
+	/// The trait from the standard library.
+	pub use core::fmt::Binary;
 	/// The trait and macro from the standard library. Only the trait is actually exposed by this crate.
 	pub use core::fmt::Debug;
 	/// The trait and macro from the standard library. Only the trait is actually exposed by this crate.
 	pub use core::fmt::Display;
+	/// The trait from the standard library.
+	pub use core::fmt::LowerExp;
+	/// The trait from the standard library.
+	pub use core::fmt::LowerHex;
+	/// The trait from the standard library.
+	pub use core::fmt::Octal;
+	/// The trait from the standard library.
+	pub use core::fmt::Pointer;
+	/// The trait from the standard library.
+	pub use core::fmt::UpperExp;
+	/// The trait from the standard library.
+	pub use core::fmt::UpperHex;
+	/// The trait from the standard library.
+	pub use core::str::FromStr;
 }
 pub use shadowed_reexport_trick::*;
@@ -0,0 +1,30 @@
+/// The error returned by implementations of [`FromStr`](core::str::FromStr) derived with
+/// [`fmt_derive::FromStr`](crate::FromStr) if the input does not match any format of the type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+	type_name: &'static str,
+}
+
+impl ParseError {
+	#[doc(hidden)]
+	pub const fn new(type_name: &'static str) -> Self {
+		ParseError { type_name }
+	}
+
+	/// The name of the type that could not be parsed.
+	pub const fn type_name(&self) -> &'static str {
+		self.type_name
+	}
+}
+
+impl core::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(
+			f,
+			"invalid `{}`: the input does not match any of its formats",
+			self.type_name
+		)
+	}
+}
+
+impl core::error::Error for ParseError {}
@@ -16,6 +16,15 @@ mod our {
 		pub unsigned: u32,
 		pub float: f32,
 	}
+
+	#[derive(Debug, Display)]
+	#[fmt(unsafe(union_bytes))]
+	#[display(rename = "Bytes")]
+	#[allow(dead_code)]
+	pub union UnionBytes {
+		pub bytes: [u8; 4],
+		pub unsigned: u32,
+	}
 }
 
 #[test]
@@ -23,3 +32,12 @@ fn union_test() {
 	assert_eq!(format!("{:?}", our::UnionDebug { unsigned: 32 }), "<UnionDebug>");
 	assert_eq!(format!("{}", our::UnionDisplay { unsigned: 32 }), "<UnionDisplay>");
 }
+
+#[test]
+fn union_bytes_test() {
+	let union = our::UnionBytes {
+		bytes: [1, 2, 0xAB, 0xFF],
+	};
+	assert_eq!(format!("{:?}", union), "UnionBytes { bytes: [0x01, 0x02, 0xab, 0xff] }");
+	assert_eq!(format!("{}", union), "Bytes { bytes: [0x01, 0x02, 0xab, 0xff] }");
+}
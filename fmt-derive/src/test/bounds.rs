@@ -0,0 +1,84 @@
+use core::marker::PhantomData;
+
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display, LowerHex};
+
+pub trait Named {
+	type Name;
+}
+
+pub struct Unprintable;
+
+impl Named for Unprintable {
+	type Name = &'static str;
+}
+
+#[derive(Debug)]
+pub struct Plain<T>(pub T);
+
+#[derive(Debug, Display)]
+#[fmt(infer_bounds)]
+pub struct Inferred<T>(pub T);
+
+#[derive(Debug)]
+#[debug(infer_bounds)]
+pub struct Marker<T, U> {
+	pub value: U,
+	#[debug(bound = "")]
+	pub marker: PhantomData<T>,
+}
+
+#[derive(Debug)]
+#[debug(bound = "T::Name: Debug")]
+pub struct Associated<T: Named>(pub T::Name);
+
+#[derive(Debug)]
+#[debug(infer_bounds)]
+pub struct AssociatedField<T: Named> {
+	#[debug(bound = "T::Name: Debug")]
+	pub name: T::Name,
+}
+
+#[derive(LowerHex)]
+#[lower_hex(infer_bounds)]
+pub struct Hex<T>(pub T);
+
+#[derive(Debug)]
+#[debug(infer_bounds)]
+pub enum Either<L, R> {
+	Left(L),
+	Right(#[debug(ignore)] R),
+}
+
+#[test]
+fn inferred_test() {
+	assert_eq!(format!("{:?}", Plain(1)), "Plain(<T>)");
+	assert_eq!(format!("{:?}", Inferred(1)), "Inferred(1)");
+	assert_eq!(format!("{}", Inferred("a")), "Inferred(a)");
+	assert_eq!(format!("{:x}", Hex(255)), "ff");
+	assert_eq!(format!("{:?}", Either::<u32, Unprintable>::Left(1)), "Left(1)");
+	assert_eq!(format!("{:?}", Either::<u32, Unprintable>::Right(Unprintable)), "Right");
+}
+
+#[test]
+fn explicit_test() {
+	assert_eq!(
+		format!(
+			"{:?}",
+			Marker::<u8, _> {
+				value: 1,
+				marker: PhantomData
+			}
+		),
+		"Marker { value: 1, marker: PhantomData<u8> }"
+	);
+	assert_eq!(
+		format!("{:?}", Associated::<Unprintable>("name")),
+		"Associated(\"name\")"
+	);
+	assert_eq!(
+		format!("{:?}", AssociatedField::<Unprintable> { name: "name" }),
+		"AssociatedField { name: \"name\" }"
+	);
+}
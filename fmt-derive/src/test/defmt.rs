@@ -0,0 +1,152 @@
+use crate::{_rt, Defmt};
+
+pub mod inner {
+	pub struct Opaque;
+}
+
+#[derive(Defmt)]
+pub struct Point {
+	pub x: u8,
+	pub y: u8,
+}
+
+#[derive(Defmt)]
+#[debug(rename_all = "snake_case", non_exhaustive = "auto")]
+pub enum Command {
+	Stop,
+	Move {
+		#[fmt(ignore)]
+		id: u32,
+		distance: i16,
+	},
+	#[debug(rename = "raw")]
+	Raw(#[debug("0x{0:04X}")] u16),
+	#[debug("flash {0} bytes")]
+	Flash(u32),
+}
+
+#[derive(Defmt)]
+#[debug(placeholder(short))]
+pub struct Holder(pub inner::Opaque, #[debug(placeholder(type_name))] pub inner::Opaque);
+
+#[derive(Defmt)]
+#[fmt(transparent)]
+pub struct Meters(pub u32);
+
+#[derive(Defmt)]
+#[defmt(layout = "fields")]
+pub struct Reading {
+	pub sensor: u8,
+	#[debug(skip_if = Option::is_none)]
+	pub value: Option<u8>,
+}
+
+/// Encodes the value returned by `value` as `defmt` does when it is logged with `{}`. The test mode of `defmt` gives
+/// every interned string the next index of a thread-local counter, so this happens on a new thread to start from the
+/// same index every time.
+fn encode<T: defmt::Format>(value: impl FnOnce() -> T + Send + 'static) -> Vec<u8> {
+	std::thread::spawn(move || {
+		defmt::export::fmt(&value());
+		defmt::export::fetch_bytes()
+	})
+	.join()
+	.unwrap()
+}
+
+/// A hand-written implementation of `defmt::Format` for a `$ty`, which calls `defmt::write!` with the given format and
+/// arguments.
+macro_rules! expected {
+	($ty:ty, $value:expr, |$this:ident| $format:literal $(, $argument:expr)*) => {{
+		struct Expected($ty);
+		impl defmt::Format for Expected {
+			fn format(&self, fmt: defmt::Formatter) {
+				let $this = &self.0;
+				defmt::write!(fmt, $format $(, $argument)*);
+			}
+		}
+		Expected($value)
+	}};
+}
+
+#[test]
+fn struct_test() {
+	assert_eq!(
+		encode(|| Point { x: 7, y: 9 }),
+		encode(|| expected!(Point, Point { x: 7, y: 9 }, |p| "Point {{ x: {}, y: {} }}", p.x, p.y))
+	);
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(encode(|| Command::Stop), encode(|| expected!((), (), |_unit| "stop")));
+	assert_eq!(
+		encode(|| Command::Move { id: 1, distance: -3 }),
+		encode(|| expected!(i16, -3, |d| "move {{ distance: {}, .. }}", d))
+	);
+	assert_eq!(
+		encode(|| Command::Raw(0xBEEF)),
+		encode(|| expected!(u16, 0xBEEF, |v| "raw(0x{:04X})", v))
+	);
+	assert_eq!(
+		encode(|| Command::Flash(4096)),
+		encode(|| expected!(u32, 4096, |s| "flash {} bytes", s))
+	);
+}
+
+#[test]
+fn placeholder_test() {
+	let type_name = core::any::type_name::<inner::Opaque>();
+	let bytes = encode(|| Holder(inner::Opaque, inner::Opaque));
+	assert_eq!(
+		bytes,
+		encode(move || expected!(
+			&'static str,
+			type_name,
+			|t| "Holder({}, {})",
+			_rt::DefmtPlaceholder::Text(defmt::intern!("<Opaque>")),
+			_rt::DefmtPlaceholder::TypeName(t)
+		))
+	);
+	assert!(
+		bytes
+			.windows(type_name.len())
+			.any(|window| window == type_name.as_bytes())
+	);
+}
+
+#[test]
+fn transparent_test() {
+	struct Expected(u32);
+	impl defmt::Format for Expected {
+		fn format(&self, fmt: defmt::Formatter) {
+			self.0.format(fmt);
+		}
+	}
+
+	assert_eq!(encode(|| Meters(5)), encode(|| Expected(5)));
+}
+
+#[test]
+fn skip_if_test() {
+	struct Expected(u8, Option<u8>);
+	impl defmt::Format for Expected {
+		fn format(&self, fmt: defmt::Formatter) {
+			defmt::write!(fmt, "sensor={}", self.0);
+			if self.1.is_some() {
+				defmt::write!(fmt, " value={}", self.1);
+			}
+		}
+	}
+
+	assert_eq!(
+		encode(|| Reading { sensor: 2, value: None }),
+		encode(|| Expected(2, None))
+	);
+	assert_eq!(
+		encode(|| Reading {
+			sensor: 2,
+			value: Some(4)
+		}),
+		encode(|| Expected(2, Some(4)))
+	);
+}
@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Error};
+
+#[derive(Debug, Error)]
+#[display("low level failure")]
+pub struct LowLevel;
+
+#[derive(Debug, Error)]
+#[display("wrapped: {0}")]
+pub struct Wrapped(#[error(from)] pub LowLevel);
+
+#[derive(Debug, Error)]
+pub enum Enum {
+	#[display("io failed at {position}")]
+	Io {
+		position: u64,
+		#[error(source)]
+		cause: LowLevel,
+	},
+	#[display("boxed")]
+	Boxed(#[error(source)] Box<dyn std::error::Error + Send + Sync>),
+	#[display("converted")]
+	Converted(#[error(from)] Wrapped),
+	#[display("no source")]
+	Plain,
+}
+
+#[derive(Debug, Error)]
+pub struct Generic<T: std::error::Error + 'static>(#[error(source)] pub T);
+
+#[test]
+fn struct_test() {
+	assert!(LowLevel.source().is_none());
+	assert_eq!(LowLevel.to_string(), "low level failure");
+
+	let wrapped = Wrapped::from(LowLevel);
+	assert_eq!(wrapped.to_string(), "wrapped: low level failure");
+	assert_eq!(wrapped.source().unwrap().to_string(), "low level failure");
+}
+
+#[test]
+fn enum_test() {
+	let io = Enum::Io {
+		position: 3,
+		cause: LowLevel,
+	};
+	assert_eq!(io.to_string(), "io failed at 3");
+	assert_eq!(io.source().unwrap().to_string(), "low level failure");
+
+	let boxed = Enum::Boxed(Box::new(LowLevel));
+	assert_eq!(boxed.source().unwrap().to_string(), "low level failure");
+
+	let converted: Enum = Wrapped(LowLevel).into();
+	assert_eq!(converted.to_string(), "converted");
+	assert_eq!(converted.source().unwrap().to_string(), "wrapped: low level failure");
+	assert_eq!(
+		converted.source().unwrap().source().unwrap().to_string(),
+		"low level failure"
+	);
+
+	assert!(Enum::Plain.source().is_none());
+}
+
+#[test]
+fn generic_test() {
+	assert_eq!(Generic(LowLevel).source().unwrap().to_string(), "low level failure");
+}
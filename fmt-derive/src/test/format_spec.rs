@@ -0,0 +1,55 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+#[derive(Debug, Display)]
+#[fmt("[{value:*^9.precision$}]")]
+pub struct Padded {
+	pub value: f64,
+	pub precision: usize,
+}
+
+#[derive(Debug, Display)]
+#[fmt("[{:.*}] [{1:>0$}]", self.digits, self.value)]
+pub struct Star {
+	pub value: f64,
+	pub digits: usize,
+}
+
+#[derive(Debug, Display)]
+#[fmt("[{0:>1$.2$}]")]
+pub struct Counts(pub f64, pub usize, pub usize);
+
+#[derive(Debug, Display)]
+#[fmt("{{{x:+#06x}}} {{}}")]
+pub struct Escaped {
+	pub x: i32,
+}
+
+#[test]
+fn padded_test() {
+	let padded = Padded {
+		value: 1.0,
+		precision: 2,
+	};
+	assert_eq!(format!("{:?}", padded), "[**1.00***]");
+	assert_eq!(format!("{}", padded), "[**1.00***]");
+}
+
+#[test]
+fn star_test() {
+	let star = Star { value: 1.5, digits: 5 };
+	assert_eq!(format!("{:?}", star), "[1.50000] [  1.5]");
+	assert_eq!(format!("{}", star), "[1.50000] [  1.5]");
+}
+
+#[test]
+fn counts_test() {
+	assert_eq!(format!("{}", Counts(1.5, 7, 2)), "[   1.50]");
+}
+
+#[test]
+fn escaped_test() {
+	assert_eq!(format!("{:?}", Escaped { x: 10 }), "{+0x00a} {}");
+	assert_eq!(format!("{}", Escaped { x: 10 }), "{+0x00a} {}");
+}
@@ -0,0 +1,50 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+mod formatters {
+	pub fn hex(value: &u32, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "{:#x}", value)
+	}
+
+	pub fn len<T, const N: usize>(value: &[T; N], f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "[{} items]", value.len())
+	}
+}
+
+pub struct Unprintable;
+
+#[derive(Debug, Display)]
+pub struct Struct {
+	#[fmt(with = formatters::hex)]
+	pub x: u32,
+	#[debug(with = formatters::len)]
+	#[display(ignore)]
+	pub y: [Unprintable; 2],
+}
+
+#[derive(Debug, Display)]
+pub struct Tuple(#[fmt(with = formatters::hex)] pub u32, pub u32);
+
+#[derive(Debug, Display)]
+pub enum Enum {
+	Variant(#[display(with = self::formatters::hex)] u32),
+}
+
+#[test]
+fn struct_test() {
+	let value = Struct {
+		x: 255,
+		y: [Unprintable, Unprintable],
+	};
+	assert_eq!(format!("{:?}", value), "Struct { x: 0xff, y: [2 items] }");
+	assert_eq!(format!("{}", value), "Struct { x: 0xff }");
+}
+
+#[test]
+fn tuple_test() {
+	assert_eq!(format!("{:?}", Tuple(255, 255)), "Tuple(0xff, 255)");
+	assert_eq!(format!("{}", Tuple(255, 255)), "Tuple(0xff, 255)");
+	assert_eq!(format!("{:?}", Enum::Variant(255)), "Variant(255)");
+	assert_eq!(format!("{}", Enum::Variant(255)), "Variant(0xff)");
+}
@@ -0,0 +1,126 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Display, FromStr, ParseError};
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display("{x},{y}")]
+pub struct Point {
+	pub x: i32,
+	pub y: i32,
+}
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display("{0}..{1} {{step {2}}}")]
+pub struct Range(pub u32, pub u32, pub u32);
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display("{host}:{port}")]
+pub struct Address {
+	pub host: String,
+	pub port: u16,
+	pub retries: u8,
+}
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display("{a}-{b}")]
+pub struct Pair {
+	pub a: String,
+	pub b: String,
+}
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display(rename_all = "kebab-case")]
+pub enum Shape {
+	#[display("circle r={radius}")]
+	Circle {
+		radius: f64,
+	},
+	#[display("rect {0}x{1}")]
+	Rect(u32, u32),
+	#[display(transparent)]
+	Named(Point),
+	EmptySet,
+}
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display(transparent)]
+pub struct Meters(pub u32);
+
+#[derive(Debug, Display, FromStr, PartialEq)]
+#[display(bound = "T: core::fmt::Display", "<{}>", self.0)]
+pub struct Generic<T>(pub T);
+
+fn round_trip<T: core::fmt::Display + core::str::FromStr<Err: core::fmt::Debug> + PartialEq + core::fmt::Debug>(
+	value: T,
+) {
+	assert_eq!(value.to_string().parse::<T>().unwrap(), value);
+}
+
+#[test]
+fn struct_test() {
+	assert_eq!(Point::from_str("1,-2"), Ok(Point { x: 1, y: -2 }));
+	assert_eq!("0..10 {step 2}".parse(), Ok(Range(0, 10, 2)));
+	assert_eq!(
+		"localhost:8080".parse(),
+		Ok(Address {
+			host: "localhost".to_owned(),
+			port: 8080,
+			retries: 0,
+		})
+	);
+	assert_eq!("7".parse(), Ok(Meters(7)));
+}
+
+#[test]
+fn backtracking_test() {
+	// the host ends at the last `:` that leaves a valid port
+	assert_eq!(
+		"[::1]:80".parse::<Address>().map(|address| address.host),
+		Ok("[::1]".to_owned())
+	);
+	assert_eq!("-1,-2".parse(), Ok(Point { x: -1, y: -2 }));
+	// the first field ends as early as possible, even if it was printed with a `-`
+	let pair = Pair {
+		a: "x-y".to_owned(),
+		b: "z".to_owned(),
+	};
+	assert_eq!(
+		pair.to_string().parse(),
+		Ok(Pair {
+			a: "x".to_owned(),
+			b: "y-z".to_owned(),
+		})
+	);
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!("circle r=1.5".parse(), Ok(Shape::Circle { radius: 1.5 }));
+	assert_eq!("rect 2x3".parse(), Ok(Shape::Rect(2, 3)));
+	assert_eq!("4,5".parse(), Ok(Shape::Named(Point { x: 4, y: 5 })));
+	assert_eq!("empty-set".parse(), Ok(Shape::EmptySet));
+}
+
+#[test]
+fn error_test() {
+	assert_eq!("1;2".parse::<Point>(), Err(ParseError::new("Point")));
+	assert_eq!("1,2,".parse::<Point>(), Err(ParseError::new("Point")));
+	assert_eq!("EmptySet".parse::<Shape>(), Err(ParseError::new("Shape")));
+	assert_eq!(
+		ParseError::new("Shape").to_string(),
+		"invalid `Shape`: the input does not match any of its formats"
+	);
+}
+
+#[test]
+fn round_trip_test() {
+	round_trip(Point { x: -3, y: 4 });
+	round_trip(Range(1, 2, 3));
+	round_trip(Shape::Circle { radius: -0.25 });
+	round_trip(Shape::Rect(10, 0));
+	round_trip(Shape::Named(Point { x: 0, y: 0 }));
+	round_trip(Shape::EmptySet);
+	round_trip(Meters(42));
+	round_trip(Generic(Point { x: 1, y: 2 }));
+	round_trip(Generic("a>b".to_owned()));
+}
@@ -39,7 +39,7 @@ fn test_a() {
 #[test]
 fn test_b() {
 	assert_ne!(format!("{:?}", std::Unit("a")), format!("{:?}", our::b::Unit(&"a")));
-	assert_eq!(format!("{:?}", our::b::Unit(&"a")), "Unit(<& 'a T>)");
+	assert_eq!(format!("{:?}", our::b::Unit(&"a")), "Unit(<&'a T>)");
 }
 
 #[test]
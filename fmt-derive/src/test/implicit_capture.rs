@@ -0,0 +1,129 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+pub struct Unprintable {
+	pub name: &'static str,
+}
+
+#[derive(Debug, Display)]
+#[fmt("{x} at {y}")]
+pub struct Named {
+	pub x: u32,
+	pub y: u32,
+}
+
+#[derive(Debug, Display)]
+#[fmt("{0} and {1:?}")]
+pub struct Tuple(pub u32, pub &'static str);
+
+#[derive(Debug, Display)]
+#[fmt("{0.name}: {self.1}")]
+pub struct Nested(pub Unprintable, pub u32);
+
+#[derive(Debug, Display)]
+#[fmt("{type}")]
+pub struct Raw {
+	pub r#type: u32,
+}
+
+#[derive(Debug, Display)]
+#[fmt("{0} {x} {0}", x = 3)]
+pub struct Explicit(pub u32);
+
+#[derive(Debug, Display)]
+pub enum Enum {
+	#[fmt("A({0}, {1:>3})")]
+	A(u32, u32),
+	#[fmt("B({inner.name}, {x:#x})")]
+	B { inner: Unprintable, x: u32 },
+}
+
+pub const MAX: u32 = 10;
+
+#[derive(Debug, Display)]
+#[fmt("{x}/{MAX}")]
+pub struct Captured {
+	pub x: u32,
+}
+
+#[derive(Debug, Display)]
+pub enum CapturedEnum {
+	#[fmt("{MAX}")]
+	Unit,
+	#[fmt("{0} of {MAX}")]
+	Tuple(u32),
+}
+
+#[derive(Debug, Display)]
+pub struct Field(#[fmt("<{0:03}>")] pub u32, #[fmt("{1}")] pub &'static str);
+
+#[test]
+fn named_test() {
+	assert_eq!(format!("{:?}", Named { x: 1, y: 2 }), "1 at 2");
+	assert_eq!(format!("{}", Named { x: 1, y: 2 }), "1 at 2");
+}
+
+#[test]
+fn tuple_test() {
+	assert_eq!(format!("{:?}", Tuple(1, "a")), "1 and \"a\"");
+	assert_eq!(format!("{}", Tuple(1, "a")), "1 and \"a\"");
+}
+
+#[test]
+fn nested_test() {
+	assert_eq!(format!("{:?}", Nested(Unprintable { name: "a" }, 1)), "a: 1");
+	assert_eq!(format!("{}", Nested(Unprintable { name: "a" }, 1)), "a: 1");
+}
+
+#[test]
+fn raw_test() {
+	assert_eq!(format!("{:?}", Raw { r#type: 1 }), "1");
+	assert_eq!(format!("{}", Raw { r#type: 1 }), "1");
+}
+
+#[test]
+fn explicit_test() {
+	assert_eq!(format!("{:?}", Explicit(1)), "1 3 1");
+	assert_eq!(format!("{}", Explicit(1)), "1 3 1");
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:?}", Enum::A(1, 2)), "A(1,   2)");
+	assert_eq!(format!("{}", Enum::A(1, 2)), "A(1,   2)");
+	assert_eq!(
+		format!(
+			"{:?}",
+			Enum::B {
+				inner: Unprintable { name: "b" },
+				x: 10
+			}
+		),
+		"B(b, 0xa)"
+	);
+	assert_eq!(
+		format!(
+			"{}",
+			Enum::B {
+				inner: Unprintable { name: "b" },
+				x: 10
+			}
+		),
+		"B(b, 0xa)"
+	);
+}
+
+#[test]
+fn field_test() {
+	assert_eq!(format!("{:?}", Field(1, "a")), "Field(<001>, a)");
+	assert_eq!(format!("{}", Field(1, "a")), "Field(<001>, a)");
+}
+
+#[test]
+fn capture_test() {
+	assert_eq!(format!("{:?}", Captured { x: 3 }), "3/10");
+	assert_eq!(format!("{}", Captured { x: 3 }), "3/10");
+	assert_eq!(format!("{}", CapturedEnum::Unit), "10");
+	assert_eq!(format!("{:?}", CapturedEnum::Tuple(4)), "4 of 10");
+}
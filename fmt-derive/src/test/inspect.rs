@@ -0,0 +1,214 @@
+use pretty_assertions::assert_eq;
+
+use crate::visit::{Inspect, Visitor};
+
+mod our {
+	use crate::{Debug, Inspect};
+
+	pub struct Unprintable;
+
+	#[derive(Debug)]
+	pub struct Opaque(pub u8);
+
+	#[derive(Inspect)]
+	#[debug(rename_all = "camelCase")]
+	pub struct Request {
+		pub request_id: u64,
+		pub path: &'static str,
+		pub retry: Option<u8>,
+		#[debug(ignore)]
+		pub cache: Unprintable,
+		#[debug(redact)]
+		pub token: &'static str,
+		pub inner: Inner,
+		pub opaque: Opaque,
+		pub unprintable: Unprintable,
+	}
+
+	#[derive(Inspect)]
+	pub struct Inner(pub bool, #[debug("{1:#x}")] pub u32);
+
+	#[derive(Inspect)]
+	#[debug(rename_all = "snake_case")]
+	pub enum Event {
+		Started,
+		Progress {
+			percent: f32,
+			#[debug(skip_if = Option::is_none)]
+			eta: Option<u32>,
+		},
+		#[debug("failed with code {0}")]
+		Failed(i32),
+	}
+
+	#[derive(Inspect)]
+	#[inspect(transparent)]
+	pub struct Meters(pub u32);
+
+	#[derive(Inspect)]
+	pub struct Generic<T>(pub T);
+
+	#[derive(Inspect)]
+	#[inspect(infer_bounds)]
+	pub struct Required<T>(pub T);
+}
+
+/// Records every call as a compact string.
+#[derive(Default)]
+struct Recorder(Vec<String>);
+
+impl Visitor for Recorder {
+	fn enter_struct(&mut self, name: &str) {
+		self.0.push(format!("{} {{", name));
+	}
+
+	fn exit_struct(&mut self) {
+		self.0.push("}".to_owned());
+	}
+
+	fn enter_tuple(&mut self, name: &str) {
+		self.0.push(format!("{}(", name));
+	}
+
+	fn exit_tuple(&mut self) {
+		self.0.push(")".to_owned());
+	}
+
+	fn enter_field(&mut self, name: &str) {
+		self.0.push(format!("{}:", name));
+	}
+
+	fn exit_field(&mut self) {
+		self.0.push(",".to_owned());
+	}
+
+	fn visit_unit(&mut self, name: &str) {
+		self.0.push(format!("unit {}", name));
+	}
+
+	fn visit_debug(&mut self, value: &dyn core::fmt::Debug) {
+		self.0.push(format!("debug {:?}", value));
+	}
+
+	fn visit_placeholder(&mut self, placeholder: &dyn core::fmt::Display) {
+		self.0.push(format!("placeholder {}", placeholder));
+	}
+
+	fn visit_str(&mut self, value: &str) {
+		self.0.push(format!("str {}", value));
+	}
+
+	fn visit_u64(&mut self, value: u64) {
+		self.0.push(format!("u64 {}", value));
+	}
+
+	fn visit_i64(&mut self, value: i64) {
+		self.0.push(format!("i64 {}", value));
+	}
+}
+
+fn record(value: &impl Inspect) -> Vec<String> {
+	let mut recorder = Recorder::default();
+	value.inspect(&mut recorder);
+	recorder.0
+}
+
+#[test]
+fn struct_test() {
+	let request = our::Request {
+		request_id: 7,
+		path: "/index",
+		retry: Some(2),
+		cache: our::Unprintable,
+		token: "secret",
+		inner: our::Inner(true, 255),
+		opaque: our::Opaque(1),
+		unprintable: our::Unprintable,
+	};
+	assert_eq!(
+		record(&request),
+		[
+			"Request {",
+			"requestId:",
+			"u64 7",
+			",",
+			"path:",
+			"str /index",
+			",",
+			"retry:",
+			"Some(",
+			"0:",
+			"u64 2",
+			",",
+			")",
+			",",
+			"token:",
+			"debug ***",
+			",",
+			"inner:",
+			"Inner(",
+			"0:",
+			"debug true",
+			",",
+			"1:",
+			"debug 0xff",
+			",",
+			")",
+			",",
+			"opaque:",
+			"debug Opaque(1)",
+			",",
+			"unprintable:",
+			"placeholder <Unprintable>",
+			",",
+			"}",
+		]
+	);
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(record(&our::Event::Started), ["unit started"]);
+	assert_eq!(
+		record(&our::Event::Progress {
+			percent: 50.0,
+			eta: None
+		}),
+		["progress {", "percent:", "debug 50.0", ",", "}"]
+	);
+	assert_eq!(
+		record(&our::Event::Progress {
+			percent: 50.0,
+			eta: Some(3)
+		}),
+		[
+			"progress {",
+			"percent:",
+			"debug 50.0",
+			",",
+			"eta:",
+			"Some(",
+			"0:",
+			"u64 3",
+			",",
+			")",
+			",",
+			"}"
+		]
+	);
+	assert_eq!(record(&our::Event::Failed(-2)), ["debug failed with code -2"]);
+}
+
+#[test]
+fn transparent_test() {
+	assert_eq!(record(&our::Meters(5)), ["u64 5"]);
+}
+
+#[test]
+fn generics_test() {
+	assert_eq!(
+		record(&our::Generic(5u8)),
+		["Generic(", "0:", "placeholder <T>", ",", ")"]
+	);
+	assert_eq!(record(&our::Required(-5i8)), ["Required(", "0:", "i64 -5", ",", ")"]);
+}
@@ -0,0 +1,389 @@
+use pretty_assertions::assert_eq;
+
+use crate::json;
+
+mod our {
+	use crate::{Debug, Json};
+
+	pub struct Unprintable;
+
+	#[derive(Debug)]
+	pub struct Opaque(pub u8);
+
+	#[derive(Json)]
+	#[json(rename_all = "camelCase")]
+	pub struct Request {
+		pub request_id: u64,
+		pub path: &'static str,
+		pub retry: Option<u8>,
+		#[debug(ignore)]
+		pub cache: Unprintable,
+		#[debug(redact)]
+		pub token: &'static str,
+		pub inner: Inner,
+		pub opaque: Opaque,
+		pub unprintable: Unprintable,
+		#[json(rename = "ratio")]
+		pub fraction: f64,
+		pub tags: [char; 2],
+	}
+
+	#[derive(Json)]
+	pub struct Owned {
+		pub name: String,
+		pub v: Vec<u8>,
+		pub boxed: Box<str>,
+		pub cow: std::borrow::Cow<'static, [i8]>,
+		pub nested: Vec<Option<String>>,
+	}
+
+	#[derive(Json)]
+	pub struct Inner(pub bool, #[debug("{1:#x}")] pub u32);
+
+	#[derive(Json)]
+	pub struct Unit;
+
+	#[derive(Json)]
+	#[debug(rename_all = "snake_case")]
+	pub enum Event {
+		Started,
+		Progress {
+			percent: f32,
+			#[debug(skip_if = Option::is_none)]
+			eta: Option<u32>,
+		},
+		#[debug("failed with code {0}")]
+		Failed(i32),
+		Log(&'static str),
+	}
+
+	#[derive(Json)]
+	#[json(transparent)]
+	pub struct Meters(pub u32);
+
+	#[derive(Json)]
+	pub struct Generic<T>(pub T);
+
+	#[derive(Json)]
+	#[json(infer_bounds)]
+	pub struct Required<T>(pub T);
+
+	#[derive(Json)]
+	#[json("point at {}/{}", self.x, self.y)]
+	pub struct Point {
+		pub x: i8,
+		pub y: i8,
+	}
+}
+
+/// A JSON value as read by [`parse`].
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+	Null,
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Array(Vec<Value>),
+	Object(Vec<(String, Value)>),
+}
+
+impl From<&str> for Value {
+	fn from(value: &str) -> Self {
+		Value::String(value.to_owned())
+	}
+}
+
+impl From<f64> for Value {
+	fn from(value: f64) -> Self {
+		Value::Number(value)
+	}
+}
+
+fn object<const N: usize>(fields: [(&str, Value); N]) -> Value {
+	Value::Object(
+		fields
+			.into_iter()
+			.map(|(name, value)| (name.to_owned(), value))
+			.collect(),
+	)
+}
+
+/// Parses a complete JSON document, panicking with the position of the first deviation from the grammar of RFC 8259.
+fn parse(input: &str) -> Value {
+	let mut parser = Parser { input, position: 0 };
+	let value = parser.value();
+	parser.whitespace();
+	assert_eq!(parser.position, input.len(), "trailing characters in {}", input);
+	value
+}
+
+struct Parser<'a> {
+	input: &'a str,
+	position: usize,
+}
+
+impl Parser<'_> {
+	fn fail(&self, expected: &str) -> ! {
+		panic!("expected {} at position {} of {}", expected, self.position, self.input)
+	}
+
+	fn peek(&self) -> Option<char> {
+		self.input[self.position..].chars().next()
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.position += c.len_utf8();
+		Some(c)
+	}
+
+	fn expect(&mut self, expected: char) {
+		if self.next() != Some(expected) {
+			self.fail(&format!("`{}`", expected));
+		}
+	}
+
+	fn whitespace(&mut self) {
+		while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+			self.position += 1;
+		}
+	}
+
+	fn value(&mut self) -> Value {
+		self.whitespace();
+		let value = match self.peek() {
+			Some('n') => self.literal("null", Value::Null),
+			Some('t') => self.literal("true", Value::Bool(true)),
+			Some('f') => self.literal("false", Value::Bool(false)),
+			Some('"') => Value::String(self.string()),
+			Some('[') => {
+				self.position += 1;
+				let mut elements = Vec::new();
+				self.sequence(']', |parser| elements.push(parser.value()));
+				Value::Array(elements)
+			}
+			Some('{') => {
+				self.position += 1;
+				let mut fields = Vec::new();
+				self.sequence('}', |parser| {
+					parser.whitespace();
+					let name = parser.string();
+					parser.whitespace();
+					parser.expect(':');
+					fields.push((name, parser.value()));
+				});
+				Value::Object(fields)
+			}
+			Some('-' | '0'..='9') => self.number(),
+			_ => self.fail("a value"),
+		};
+		self.whitespace();
+		value
+	}
+
+	fn literal(&mut self, literal: &str, value: Value) -> Value {
+		if !self.input[self.position..].starts_with(literal) {
+			self.fail(literal);
+		}
+		self.position += literal.len();
+		value
+	}
+
+	/// Parses the elements of an array or object after the opening bracket, up to and including `end`.
+	fn sequence(&mut self, end: char, mut element: impl FnMut(&mut Self)) {
+		self.whitespace();
+		if self.peek() == Some(end) {
+			self.position += 1;
+			return;
+		}
+		loop {
+			element(self);
+			match self.next() {
+				Some(',') => {}
+				Some(c) if c == end => return,
+				_ => self.fail(&format!("`,` or `{}`", end)),
+			}
+		}
+	}
+
+	fn digits(&mut self) -> usize {
+		let start = self.position;
+		while let Some('0'..='9') = self.peek() {
+			self.position += 1;
+		}
+		self.position - start
+	}
+
+	fn number(&mut self) -> Value {
+		let start = self.position;
+		if self.peek() == Some('-') {
+			self.position += 1;
+		}
+		if self.peek() == Some('0') {
+			self.position += 1;
+		} else if self.digits() == 0 {
+			self.fail("a digit");
+		}
+		if self.peek() == Some('.') {
+			self.position += 1;
+			if self.digits() == 0 {
+				self.fail("a digit");
+			}
+		}
+		if let Some('e' | 'E') = self.peek() {
+			self.position += 1;
+			if let Some('+' | '-') = self.peek() {
+				self.position += 1;
+			}
+			if self.digits() == 0 {
+				self.fail("a digit");
+			}
+		}
+		Value::Number(self.input[start..self.position].parse().unwrap())
+	}
+
+	fn string(&mut self) -> String {
+		self.expect('"');
+		let mut string = String::new();
+		loop {
+			match self.next() {
+				Some('"') => return string,
+				Some('\\') => match self.next() {
+					Some(c @ ('"' | '\\' | '/')) => string.push(c),
+					Some('b') => string.push('\u{8}'),
+					Some('f') => string.push('\u{c}'),
+					Some('n') => string.push('\n'),
+					Some('r') => string.push('\r'),
+					Some('t') => string.push('\t'),
+					Some('u') => {
+						let hex = self.input.get(self.position..self.position + 4);
+						match hex
+							.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+							.and_then(char::from_u32)
+						{
+							Some(c) => string.push(c),
+							None => self.fail("four hex digits of a scalar value"),
+						}
+						self.position += 4;
+					}
+					_ => self.fail("an escape sequence"),
+				},
+				Some('\u{0}'..='\u{1f}') | None => self.fail("an unescaped string character"),
+				Some(c) => string.push(c),
+			}
+		}
+	}
+}
+
+fn render(value: &impl crate::JsonDisplay) -> Value {
+	parse(&json(value).to_string())
+}
+
+#[test]
+fn struct_test() {
+	let request = our::Request {
+		request_id: 7,
+		path: "/index",
+		retry: Some(2),
+		cache: our::Unprintable,
+		token: "secret",
+		inner: our::Inner(true, 255),
+		opaque: our::Opaque(1),
+		unprintable: our::Unprintable,
+		fraction: 0.25,
+		tags: ['a', 'b'],
+	};
+	assert_eq!(
+		json(&request).to_string(),
+		r#"{"requestId":7,"path":"/index","retry":2,"token":"***","inner":[true,"0xff"],"opaque":"Opaque(1)","unprintable":"<Unprintable>","ratio":0.25,"tags":["a","b"]}"#
+	);
+	assert_eq!(
+		render(&request),
+		object([
+			("requestId", 7.0.into()),
+			("path", "/index".into()),
+			("retry", 2.0.into()),
+			("token", "***".into()),
+			("inner", Value::Array(vec![Value::Bool(true), "0xff".into()])),
+			("opaque", "Opaque(1)".into()),
+			("unprintable", "<Unprintable>".into()),
+			("ratio", 0.25.into()),
+			("tags", Value::Array(vec!["a".into(), "b".into()])),
+		])
+	);
+	assert_eq!(json(&our::Unit).to_string(), r#""Unit""#);
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(json(&our::Event::Started).to_string(), r#""started""#);
+	assert_eq!(
+		render(&our::Event::Progress {
+			percent: 50.0,
+			eta: None
+		}),
+		object([("progress", object([("percent", 50.0.into())]))])
+	);
+	assert_eq!(
+		render(&our::Event::Progress {
+			percent: 50.0,
+			eta: Some(3)
+		}),
+		object([("progress", object([("percent", 50.0.into()), ("eta", 3.0.into())]))])
+	);
+	assert_eq!(render(&our::Event::Failed(-2)), "failed with code -2".into());
+	assert_eq!(json(&our::Event::Log("done")).to_string(), r#"{"log":["done"]}"#);
+}
+
+#[test]
+fn escape_test() {
+	let text = "quote \" backslash \\ slash / newline \n tab \t bell \u{7} delete \u{7f} unicode \u{e9}\u{1f600}";
+	assert_eq!(
+		render(&our::Event::Log(text)),
+		object([("log", Value::Array(vec![text.into()]))])
+	);
+	assert_eq!(json("\"\\\n\u{1}").to_string(), r#""\"\\\n\u0001""#);
+	assert_eq!(render(&our::Point { x: -1, y: 2 }), "point at -1/2".into());
+}
+
+#[test]
+fn number_test() {
+	assert_eq!(
+		json(&[f64::NAN, f64::INFINITY, -0.0, 1e300]).to_string(),
+		format!("[null,null,-0,1{}]", "0".repeat(300))
+	);
+	assert_eq!(
+		render(&[f64::MIN_POSITIVE, 1e-7]),
+		Value::Array(vec![f64::MIN_POSITIVE.into(), 1e-7.into()])
+	);
+	assert_eq!(format!("{:+>8}", json(&5i32)), "5");
+	assert_eq!(json(&(u128::MAX)).to_string(), u128::MAX.to_string());
+}
+
+#[test]
+fn alloc_test() {
+	let owned = our::Owned {
+		name: "abc".to_owned(),
+		v: vec![1, 2],
+		boxed: "d\"e".into(),
+		cow: std::borrow::Cow::Borrowed(&[-1]),
+		nested: vec![Some("f".to_owned()), None],
+	};
+	assert_eq!(
+		json(&owned).to_string(),
+		r#"{"name":"abc","v":[1,2],"boxed":"d\"e","cow":[-1],"nested":["f",null]}"#
+	);
+	assert_eq!(json(&std::sync::Arc::new(vec!['x'])).to_string(), r#"["x"]"#);
+}
+
+#[test]
+fn transparent_test() {
+	assert_eq!(json(&our::Meters(5)).to_string(), "5");
+}
+
+#[test]
+fn generics_test() {
+	assert_eq!(json(&our::Generic(5u8)).to_string(), r#"["<T>"]"#);
+	assert_eq!(json(&our::Required(-5i8)).to_string(), "[-5]");
+	assert_eq!(json(&our::Required(Some("x"))).to_string(), r#"["x"]"#);
+}
@@ -0,0 +1,85 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+pub struct Unprintable;
+
+#[derive(Debug, Display)]
+#[display(layout = "fields")]
+pub struct Fields {
+	pub a: u32,
+	#[display(rename = "bee")]
+	pub b: &'static str,
+	#[display(ignore)]
+	pub c: u32,
+	pub d: Unprintable,
+}
+
+#[derive(Debug, Display)]
+#[display(layout = "values")]
+pub struct Values(pub u32, pub &'static str, #[display("<{}>", self.0)] pub u32);
+
+#[derive(Debug, Display)]
+#[display(layout = "logfmt", rename_all = "camelCase")]
+pub enum Logfmt {
+	Request {
+		method: &'static str,
+		path: &'static str,
+		user_agent: &'static str,
+	},
+	Tuple(&'static str, u32),
+	Empty,
+}
+
+#[derive(Debug, Display)]
+#[fmt(layout = "debug")]
+pub struct Debugged {
+	pub a: u32,
+}
+
+#[derive(Debug)]
+#[debug(layout = "fields")]
+pub struct DebugFields {
+	pub a: &'static str,
+}
+
+#[test]
+fn fields_test() {
+	let fields = Fields {
+		a: 1,
+		b: "two",
+		c: 3,
+		d: Unprintable,
+	};
+	assert_eq!(format!("{}", fields), "a=1 bee=two d=<Unprintable>");
+	assert_eq!(
+		format!("{:?}", fields),
+		"Fields { a: 1, b: \"two\", c: 3, d: <Unprintable> }"
+	);
+}
+
+#[test]
+fn values_test() {
+	assert_eq!(format!("{}", Values(1, "two", 3)), "1, two, <1>");
+}
+
+#[test]
+fn logfmt_test() {
+	let request = Logfmt::Request {
+		method: "GET",
+		path: "/a=b",
+		user_agent: "say \"hi\"\n",
+	};
+	assert_eq!(
+		format!("{}", request),
+		r#"method=GET path="/a=b" userAgent="say \"hi\"\n""#
+	);
+	assert_eq!(format!("{}", Logfmt::Tuple("", 2)), r#"0="" 1=2"#);
+	assert_eq!(format!("{}", Logfmt::Empty), "empty");
+}
+
+#[test]
+fn debug_test() {
+	assert_eq!(format!("{}", Debugged { a: 1 }), "Debugged { a: 1 }");
+	assert_eq!(format!("{:?}", DebugFields { a: "x y" }), "a=\"x y\"");
+}
@@ -3,8 +3,33 @@
 mod basic_enum;
 mod basic_struct;
 mod basic_union;
+mod bounds;
 mod custom_fmt_enum;
+#[cfg(feature = "defmt")]
+mod defmt;
+mod error;
+mod format_spec;
+mod format_with;
+mod from_str;
 mod generics;
 mod ignored_field;
+mod implicit_capture;
+mod inspect;
+mod json;
+mod layout;
+mod non_exhaustive;
+mod numeric_traits;
+mod pad;
+mod placeholder;
+mod precedence;
+mod redact;
+mod rename;
+mod skip_if;
+mod strict;
+mod tagged_union;
+mod transparent;
+#[cfg(feature = "ufmt")]
+mod ufmt;
+mod unit_display;
 
 mod readme;
@@ -0,0 +1,102 @@
+use pretty_assertions::assert_eq;
+
+mod our {
+	// We disable the dead_code analysis, as it intentionally ignores usages via `derive(Debug)`
+	#![allow(dead_code)]
+
+	use crate::{Debug, Display};
+
+	#[derive(Debug)]
+	#[debug(non_exhaustive)]
+	pub struct Always {
+		pub a: u32,
+	}
+
+	#[derive(Debug)]
+	#[debug(non_exhaustive = "auto")]
+	pub struct Config {
+		pub a: u32,
+		#[debug(ignore)]
+		pub b: u32,
+	}
+
+	#[derive(Debug)]
+	#[debug(non_exhaustive = "auto")]
+	pub struct Complete(pub u32);
+
+	#[derive(Debug)]
+	#[debug(non_exhaustive = "auto")]
+	#[non_exhaustive]
+	pub struct Marked(pub u32);
+
+	#[derive(Debug, Display)]
+	#[fmt(non_exhaustive = "auto")]
+	pub enum Enum {
+		Tuple(u32, #[fmt(ignore)] u32),
+		#[non_exhaustive]
+		Struct {
+			x: u32,
+		},
+		Complete {
+			x: u32,
+		},
+	}
+
+	#[derive(Debug)]
+	#[debug(non_exhaustive, layout = "values")]
+	pub struct Values(pub u32, pub u32);
+
+	#[derive(Debug)]
+	#[debug(non_exhaustive = "auto")]
+	pub struct Skipped {
+		pub a: u32,
+		#[debug(skip_if = Option::is_none)]
+		pub b: Option<u32>,
+	}
+
+	#[derive(Display)]
+	#[display(non_exhaustive = "auto", layout = "logfmt")]
+	pub struct Logfmt {
+		pub a: u32,
+		#[display(ignore)]
+		pub b: u32,
+	}
+}
+
+#[test]
+fn struct_test() {
+	assert_eq!(format!("{:?}", our::Always { a: 1 }), "Always { a: 1, .. }");
+	assert_eq!(format!("{:?}", our::Config { a: 1, b: 2 }), "Config { a: 1, .. }");
+	assert_eq!(
+		format!("{:#?}", our::Config { a: 1, b: 2 }),
+		"Config {\n    a: 1,\n    ..\n}"
+	);
+}
+
+#[test]
+fn skip_if_test() {
+	assert_eq!(format!("{:?}", our::Skipped { a: 1, b: None }), "Skipped { a: 1, .. }");
+	assert_eq!(
+		format!("{:?}", our::Skipped { a: 1, b: Some(2) }),
+		"Skipped { a: 1, b: Some(2), .. }"
+	);
+}
+
+#[test]
+fn tuple_test() {
+	assert_eq!(format!("{:?}", our::Complete(1)), "Complete(1)");
+	assert_eq!(format!("{:?}", our::Marked(1)), "Marked(1, ..)");
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:?}", our::Enum::Tuple(1, 2)), "Tuple(1, ..)");
+	assert_eq!(format!("{}", our::Enum::Struct { x: 1 }), "Struct { x: 1, .. }");
+	assert_eq!(format!("{:?}", our::Enum::Complete { x: 1 }), "Complete { x: 1 }");
+}
+
+#[test]
+fn layout_test() {
+	assert_eq!(format!("{:?}", our::Values(1, 2)), "1, 2, ..");
+	assert_eq!(format!("{}", our::Logfmt { a: 1, b: 2 }), "a=1");
+}
@@ -0,0 +1,84 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex};
+
+pub struct Unprintable;
+
+#[derive(LowerHex, UpperHex, Binary, Octal)]
+pub struct Register(pub u32);
+
+#[derive(LowerHex)]
+pub struct Named {
+	pub value: u8,
+	#[lower_hex(ignore)]
+	pub ignored: Unprintable,
+}
+
+#[derive(LowerExp, UpperExp)]
+pub struct Float(pub f64);
+
+#[derive(Pointer)]
+pub struct Ref<'a>(pub &'a u32);
+
+#[derive(LowerHex)]
+pub struct Opaque(pub Unprintable);
+
+#[derive(LowerHex, UpperHex)]
+pub struct Pair {
+	pub a: u32,
+	#[upper_hex("{:#06X}", self.b)]
+	pub b: u32,
+}
+
+#[derive(LowerHex)]
+#[lower_hex("0x{0:08x}")]
+pub struct Custom(pub u32);
+
+#[derive(LowerHex)]
+pub enum Enum {
+	Unit,
+	Tuple(u32),
+	Struct { a: u32, b: Unprintable },
+}
+
+#[test]
+fn newtype_test() {
+	assert_eq!(format!("{:x}", Register(255)), "ff");
+	assert_eq!(format!("{:#X}", Register(255)), "0xFF");
+	assert_eq!(format!("{:010b}", Register(5)), "0000000101");
+	assert_eq!(format!("{:o}", Register(8)), "10");
+	assert_eq!(
+		format!(
+			"{:04x}",
+			Named {
+				value: 10,
+				ignored: Unprintable
+			}
+		),
+		"000a"
+	);
+	assert_eq!(format!("{:e}", Float(1234.5)), "1.2345e3");
+	assert_eq!(format!("{:.1E}", Float(1234.5)), "1.2E3");
+	assert_eq!(format!("{:x}", Opaque(Unprintable)), "<Unprintable>");
+
+	let value = 0;
+	assert_eq!(format!("{:p}", Ref(&value)), format!("{:p}", &value));
+}
+
+#[test]
+fn struct_test() {
+	assert_eq!(format!("{:x}", Pair { a: 255, b: 255 }), "Pair { a: ff, b: ff }");
+	assert_eq!(format!("{:X}", Pair { a: 255, b: 255 }), "Pair { a: FF, b: 0x00FF }");
+	assert_eq!(format!("{:x}", Custom(255)), "0x000000ff");
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:x}", Enum::Unit), "Unit");
+	assert_eq!(format!("{:x}", Enum::Tuple(255)), "ff");
+	assert_eq!(format!("{:#06x}", Enum::Tuple(255)), "0x00ff");
+	assert_eq!(
+		format!("{:x}", Enum::Struct { a: 255, b: Unprintable }),
+		"Struct { a: ff, b: <Unprintable> }"
+	);
+}
@@ -0,0 +1,66 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+#[derive(Debug, Display)]
+#[fmt(pad, "{x}/{y}")]
+pub struct Point {
+	pub x: i32,
+	pub y: i32,
+}
+
+#[derive(Debug, Display)]
+#[display(pad)]
+pub struct Pair(pub u32, pub &'static str);
+
+#[derive(Debug, Display)]
+pub enum Cell {
+	#[display(pad, "ä{0}")]
+	Padded(u32),
+	#[display("{0}")]
+	Unpadded(u32),
+}
+
+#[derive(Debug)]
+#[debug(pad)]
+pub struct Nested {
+	pub inner: u32,
+}
+
+#[test]
+fn format_test() {
+	let point = Point { x: 1, y: -2 };
+	assert_eq!(format!("[{:>8}]", point), "[    1/-2]");
+	assert_eq!(format!("[{:*<8?}]", point), "[1/-2****]");
+	assert_eq!(format!("[{:^9}]", point), "[  1/-2   ]");
+	assert_eq!(format!("[{:.3}]", point), "[1/-]");
+	assert_eq!(format!("[{:6.2}]", point), "[1/    ]");
+	assert_eq!(format!("[{}]", point), "[1/-2]");
+}
+
+#[test]
+fn sign_test() {
+	assert_eq!(format!("[{:+22?}]", Nested { inner: 1 }), "[Nested { inner: +1 }  ]");
+	assert_eq!(format!("[{:08}]", Point { x: 1, y: -2 }), "[00001/-2]");
+	assert_eq!(format!("[{:08}]", Point { x: -1, y: 2 }), "[-00001/2]");
+	assert_eq!(format!("[{:*<08}]", Point { x: -1, y: 2 }), "[-00001/2]");
+	assert_eq!(format!("[{:06.3}]", Point { x: -1, y: 2 }), "[-0001/]");
+}
+
+#[test]
+fn default_test() {
+	assert_eq!(format!("[{:>12}]", Pair(1, "a")), "[  Pair(1, a)]");
+	assert_eq!(format!("[{:4}]", Pair(1, "a")), "[Pair(1, a)]");
+}
+
+#[test]
+fn variant_test() {
+	assert_eq!(format!("[{:>4}]", Cell::Padded(1)), "[  ä1]");
+	assert_eq!(format!("[{:>4}]", Cell::Unpadded(1)), "[1]");
+}
+
+#[test]
+fn alternate_test() {
+	assert_eq!(format!("{:>1?}", Nested { inner: 1 }), "Nested { inner: 1 }");
+	assert_eq!(format!("{:#1?}", Nested { inner: 1 }), "Nested {\n    inner: 1,\n}");
+}
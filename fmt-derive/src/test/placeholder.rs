@@ -0,0 +1,87 @@
+use pretty_assertions::assert_eq;
+
+pub mod inner {
+	pub struct Unprintable;
+}
+
+mod our {
+	// We disable the dead_code analysis, as it intentionally ignores usages via `derive(Debug)`
+	#![allow(dead_code)]
+
+	use crate::Debug;
+
+	fn last_segment(type_name: &str, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "<{}?>", type_name.rsplit("::").next().unwrap_or(type_name))
+	}
+
+	#[derive(Debug)]
+	pub struct Path {
+		pub list: Vec<super::inner::Unprintable>,
+		pub pair: (&'static dyn Fn(u8) -> u8, [super::inner::Unprintable; 2]),
+		pub array: [&'static mut super::inner::Unprintable; 0],
+	}
+
+	#[derive(Debug)]
+	#[debug(placeholder(short))]
+	pub struct Short {
+		pub list: Vec<super::inner::Unprintable>,
+		#[debug(placeholder(path))]
+		pub single: super::inner::Unprintable,
+	}
+
+	#[derive(Debug)]
+	#[debug(placeholder(type_name))]
+	pub struct TypeName(pub super::inner::Unprintable);
+
+	#[derive(Debug)]
+	#[debug(placeholder(text = "?"))]
+	pub struct Text(pub super::inner::Unprintable, pub u32);
+
+	#[derive(Debug)]
+	pub struct With(#[debug(placeholder(with = last_segment))] pub super::inner::Unprintable);
+}
+
+#[test]
+fn path_test() {
+	let path = our::Path {
+		list: vec![],
+		pair: (&|x| x, [inner::Unprintable, inner::Unprintable]),
+		array: [],
+	};
+	assert_eq!(
+		format!("{:?}", path),
+		"Path { list: <Vec<super::inner::Unprintable>>, \
+		 pair: <(&'static dyn Fn(u8) -> u8, [super::inner::Unprintable; 2])>, \
+		 array: <[&'static mut super::inner::Unprintable; 0]> }"
+	);
+}
+
+#[test]
+fn short_test() {
+	let short = our::Short {
+		list: vec![],
+		single: inner::Unprintable,
+	};
+	assert_eq!(
+		format!("{:?}", short),
+		"Short { list: <Vec<Unprintable>>, single: <super::inner::Unprintable> }"
+	);
+}
+
+#[test]
+fn type_name_test() {
+	assert_eq!(
+		format!("{:?}", our::TypeName(inner::Unprintable)),
+		format!("TypeName(<{}>)", core::any::type_name::<inner::Unprintable>())
+	);
+}
+
+#[test]
+fn text_test() {
+	assert_eq!(format!("{:?}", our::Text(inner::Unprintable, 1)), "Text(?, 1)");
+}
+
+#[test]
+fn with_test() {
+	assert_eq!(format!("{:?}", our::With(inner::Unprintable)), "With(<Unprintable?>)");
+}
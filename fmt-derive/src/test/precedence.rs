@@ -0,0 +1,45 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+#[derive(Debug, Display)]
+#[debug("debug")]
+#[fmt("shared")]
+pub struct Item;
+
+#[derive(Debug, Display)]
+pub struct Field {
+	#[debug(redact)]
+	#[fmt(format = "<{}>", self.secret)]
+	pub secret: u32,
+	#[fmt(ignore)]
+	#[display("{}", self.hidden)]
+	pub hidden: u32,
+}
+
+#[derive(Debug, Display)]
+pub enum Enum {
+	#[fmt(rename = "shared")]
+	#[display(rename = "display")]
+	#[display(rename_all = "UPPERCASE")]
+	Variant { value: u32 },
+}
+
+#[test]
+fn item_test() {
+	assert_eq!(format!("{:?}", Item), "debug");
+	assert_eq!(format!("{}", Item), "shared");
+}
+
+#[test]
+fn field_test() {
+	let field = Field { secret: 1, hidden: 2 };
+	assert_eq!(format!("{:?}", field), "Field { secret: *** }");
+	assert_eq!(format!("{}", field), "Field { secret: <1>, hidden: 2 }");
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:?}", Enum::Variant { value: 1 }), "shared { value: 1 }");
+	assert_eq!(format!("{}", Enum::Variant { value: 1 }), "display { VALUE: 1 }");
+}
@@ -0,0 +1,49 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+fn initial(value: &&'static str, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+	write!(f, "{}...", &value[..1])
+}
+
+#[derive(Debug)]
+pub struct Credentials {
+	pub user: &'static str,
+	#[debug(redact)]
+	pub password: &'static str,
+	#[debug(redact(length))]
+	pub pin: u32,
+	#[debug(redact(last = 4))]
+	pub card: &'static str,
+	#[debug(redact(with = initial))]
+	pub name: &'static str,
+}
+
+#[derive(Debug, Display)]
+pub struct Token(
+	#[fmt(redact)] pub &'static str,
+	#[fmt(redact(last = 2))] pub &'static str,
+);
+
+#[test]
+fn struct_test() {
+	assert_eq!(
+		format!(
+			"{:?}",
+			Credentials {
+				user: "admin",
+				password: "hunter2",
+				pin: 1234,
+				card: "1234567812345678",
+				name: "Alice",
+			}
+		),
+		"Credentials { user: \"admin\", password: ***, pin: ****, card: ************5678, name: A... }"
+	);
+}
+
+#[test]
+fn tuple_test() {
+	assert_eq!(format!("{:?}", Token("secret", "ab")), "Token(***, ab)");
+	assert_eq!(format!("{}", Token("secret", "abcd")), "Token(***, **cd)");
+}
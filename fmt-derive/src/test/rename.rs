@@ -0,0 +1,80 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+#[derive(Debug, Display)]
+#[fmt(rename = "renamed")]
+pub struct Struct {
+	#[debug(rename = "ID")]
+	pub id: u32,
+	pub r#type: u32,
+}
+
+#[derive(Debug)]
+#[debug(rename_all = "camelCase")]
+pub struct RenameAll {
+	pub first_field: u32,
+	#[debug(rename = "second")]
+	pub second_field: u32,
+}
+
+#[derive(Debug, Display)]
+#[fmt(rename_all = "kebab-case")]
+pub enum Enum {
+	PlainUnit,
+	SingleTuple(u32),
+	#[display(rename = "renamed")]
+	NamedStruct {
+		some_field: u32,
+	},
+	#[fmt(rename_all = "SCREAMING_SNAKE_CASE")]
+	OtherFields {
+		some_field: u32,
+	},
+}
+
+#[derive(Debug)]
+#[debug(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Acronyms {
+	HTTPServer,
+	IOError,
+}
+
+#[test]
+fn struct_test() {
+	assert_eq!(
+		format!("{:?}", Struct { id: 1, r#type: 2 }),
+		"renamed { ID: 1, type: 2 }"
+	);
+	assert_eq!(format!("{}", Struct { id: 1, r#type: 2 }), "renamed { id: 1, type: 2 }");
+	assert_eq!(
+		format!(
+			"{:?}",
+			RenameAll {
+				first_field: 1,
+				second_field: 2
+			}
+		),
+		"RenameAll { firstField: 1, second: 2 }"
+	);
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:?}", Enum::PlainUnit), "plain-unit");
+	assert_eq!(format!("{:?}", Enum::SingleTuple(1)), "single-tuple(1)");
+	assert_eq!(
+		format!("{:?}", Enum::NamedStruct { some_field: 1 }),
+		"named-struct { some-field: 1 }"
+	);
+	assert_eq!(
+		format!("{}", Enum::NamedStruct { some_field: 1 }),
+		"renamed { some-field: 1 }"
+	);
+	assert_eq!(
+		format!("{:?}", Enum::OtherFields { some_field: 1 }),
+		"other-fields { SOME_FIELD: 1 }"
+	);
+	assert_eq!(format!("{:?}", Acronyms::HTTPServer), "HTTP-SERVER");
+	assert_eq!(format!("{:?}", Acronyms::IOError), "IO-ERROR");
+}
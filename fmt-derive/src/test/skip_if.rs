@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+mod our {
+	use crate::{Debug, Display};
+
+	fn is_zero(value: &u32) -> bool {
+		*value == 0
+	}
+
+	#[derive(Debug)]
+	pub struct Config {
+		#[debug(skip_if = Option::is_none)]
+		pub name: Option<&'static str>,
+		#[debug(skip_if = Vec::is_empty)]
+		pub tags: Vec<&'static str>,
+		pub retries: u32,
+	}
+
+	#[derive(Debug, Display)]
+	pub struct Tuple(#[fmt(skip_if = is_zero)] pub u32, pub u32);
+
+	#[derive(Debug, Display)]
+	pub enum Enum {
+		Struct {
+			#[fmt(skip_if = is_zero)]
+			x: u32,
+			y: u32,
+		},
+	}
+
+	#[derive(Debug, Display)]
+	#[fmt(transparent)]
+	pub struct Newtype(#[fmt(skip_if = is_zero)] pub u32);
+}
+
+#[test]
+fn struct_test() {
+	let config = our::Config {
+		name: None,
+		tags: vec![],
+		retries: 3,
+	};
+	assert_eq!(format!("{:?}", config), "Config { retries: 3 }");
+
+	let config = our::Config {
+		name: Some("main"),
+		tags: vec!["a"],
+		retries: 3,
+	};
+	assert_eq!(
+		format!("{:?}", config),
+		r#"Config { name: Some("main"), tags: ["a"], retries: 3 }"#
+	);
+}
+
+#[test]
+fn tuple_test() {
+	assert_eq!(format!("{:?}", our::Tuple(0, 2)), "Tuple(2)");
+	assert_eq!(format!("{}", our::Tuple(1, 2)), "Tuple(1, 2)");
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:?}", our::Enum::Struct { x: 0, y: 2 }), "Struct { y: 2 }");
+	assert_eq!(format!("{}", our::Enum::Struct { x: 1, y: 2 }), "Struct { x: 1, y: 2 }");
+}
+
+#[test]
+fn transparent_test() {
+	assert_eq!(format!("{}", our::Newtype(0)), "");
+	assert_eq!(format!("{:?}", our::Newtype(7)), "7");
+}
@@ -0,0 +1,49 @@
+use pretty_assertions::assert_eq;
+
+struct Unprintable;
+
+mod our {
+	use crate::{Debug, Display};
+
+	#[derive(Debug, Display)]
+	#[fmt(strict)]
+	pub struct Printable {
+		pub a: u32,
+		#[debug(strict = "allow")]
+		#[display(ignore)]
+		pub b: super::Unprintable,
+	}
+
+	#[derive(Debug)]
+	#[debug(strict, bound = "T: core::fmt::Debug")]
+	pub struct Generic<T>(pub T);
+
+	pub mod warn {
+		#![allow(deprecated)]
+
+		use crate::Debug;
+
+		#[derive(Debug)]
+		#[debug(strict = "warn")]
+		pub struct Warn(pub u32, pub super::super::Unprintable);
+	}
+}
+
+#[test]
+fn strict_test() {
+	let printable = our::Printable { a: 1, b: Unprintable };
+	assert_eq!(
+		format!("{:?}", printable),
+		"Printable { a: 1, b: <super::Unprintable> }"
+	);
+	assert_eq!(format!("{}", printable), "Printable { a: 1 }");
+	assert_eq!(format!("{:?}", our::Generic(1)), "Generic(1)");
+}
+
+#[test]
+fn warn_test() {
+	assert_eq!(
+		format!("{:?}", our::warn::Warn(1, Unprintable)),
+		"Warn(1, <super::super::Unprintable>)"
+	);
+}
@@ -0,0 +1,84 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display, LowerHex};
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Key {
+	pub kind: u32,
+	pub code: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Mouse {
+	pub kind: u32,
+	pub x: i16,
+	pub y: i16,
+}
+
+#[derive(Debug, Display, LowerHex)]
+#[fmt(unsafe(variant = Self::active), rename_all = "UPPERCASE")]
+#[repr(C)]
+pub union Event {
+	#[fmt(ignore)]
+	pub kind: u32,
+	#[display("{key.code}")]
+	pub key: Key,
+	pub mouse: Mouse,
+}
+
+impl Event {
+	fn active(&self) -> &'static str {
+		// SAFETY: all fields start with `kind`
+		match unsafe { self.kind } {
+			1 => "key",
+			2 => "mouse",
+			_ => "unknown",
+		}
+	}
+}
+
+#[derive(Debug)]
+#[debug(unsafe(variant = if self.is_small() { "small" } else { "large" }))]
+pub union Number {
+	pub small: u8,
+	pub large: u32,
+}
+
+impl Number {
+	fn is_small(&self) -> bool {
+		// SAFETY: both fields are plain integers, of which `small` is the shorter one
+		unsafe { self.large <= 0xFF }
+	}
+}
+
+#[test]
+fn method_test() {
+	let key = Event {
+		key: Key { kind: 1, code: 65 },
+	};
+	assert_eq!(format!("{:?}", key), "Event { KEY: Key { kind: 1, code: 65 } }");
+	assert_eq!(format!("{}", key), "Event { KEY: 65 }");
+
+	let mouse = Event {
+		mouse: Mouse { kind: 2, x: 3, y: -4 },
+	};
+	assert_eq!(
+		format!("{:?}", mouse),
+		"Event { MOUSE: Mouse { kind: 2, x: 3, y: -4 } }"
+	);
+	assert_eq!(format!("{}", mouse), "Event { MOUSE: <Mouse> }");
+}
+
+#[test]
+fn unknown_test() {
+	assert_eq!(format!("{:?}", Event { kind: 7 }), "<Event>");
+	assert_eq!(format!("{:x}", Event { kind: 7 }), "<Event>");
+}
+
+#[test]
+fn expression_test() {
+	assert_eq!(format!("{:?}", Number { large: 0 }), "Number { small: 0 }");
+	assert_eq!(format!("{:?}", Number { large: 0x1234 }), "Number { large: 4660 }");
+}
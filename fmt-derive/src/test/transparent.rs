@@ -0,0 +1,74 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+pub struct Unprintable;
+
+#[derive(Debug, Display)]
+#[fmt(transparent)]
+pub struct UserId(pub u64);
+
+#[derive(Debug, Display)]
+#[display(transparent)]
+pub struct Price {
+	pub amount: f64,
+	#[display(ignore)]
+	pub currency: &'static str,
+}
+
+#[derive(Debug, Display)]
+pub enum Value {
+	#[display(transparent)]
+	Text(&'static str),
+	#[fmt(transparent)]
+	Opaque(Unprintable),
+	Pair(u32, u32),
+}
+
+#[derive(Debug, Display)]
+#[debug(transparent)]
+pub enum Number {
+	Integer(i64),
+	Float { value: f64 },
+}
+
+#[derive(Debug, Display)]
+#[debug(transparent)]
+pub struct Optional(#[debug(skip_if = Option::is_none)] pub Option<u32>);
+
+#[test]
+fn struct_test() {
+	assert_eq!(format!("{:?}", UserId(42)), "42");
+	assert_eq!(format!("[{:>6}]", UserId(42)), "[    42]");
+	assert_eq!(format!("[{:<6?}]", UserId(42)), "[42    ]");
+
+	let price = Price {
+		amount: 1.5,
+		currency: "EUR",
+	};
+	assert_eq!(format!("{:.2}", price), "1.50");
+	assert_eq!(format!("{:?}", price), "Price { amount: 1.5, currency: \"EUR\" }");
+}
+
+#[test]
+fn variant_test() {
+	assert_eq!(format!("[{:^7}]", Value::Text("abc")), "[  abc  ]");
+	assert_eq!(format!("{:?}", Value::Text("abc")), "Text(\"abc\")");
+	assert_eq!(format!("{}", Value::Opaque(Unprintable)), "<Unprintable>");
+	assert_eq!(format!("{}", Value::Pair(1, 2)), "Pair(1, 2)");
+}
+
+#[test]
+fn enum_test() {
+	assert_eq!(format!("{:+?}", Number::Integer(3)), "+3");
+	assert_eq!(format!("{:.1?}", Number::Float { value: 2.0 }), "2.0");
+	assert_eq!(format!("{}", Number::Integer(3)), "Integer(3)");
+}
+
+#[test]
+fn skip_if_test() {
+	assert_eq!(format!("[{:>4?}]", Optional(None)), "[    ]");
+	assert_eq!(format!("[{:*<3?}]", Optional(None)), "[***]");
+	assert_eq!(format!("[{:?}]", Optional(None)), "[]");
+	assert_eq!(format!("[{:?}]", Optional(Some(1))), "[Some(1)]");
+}
@@ -0,0 +1,144 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display, UDebug, UDisplay};
+
+pub mod inner {
+	pub struct Opaque;
+}
+
+#[derive(Debug, UDebug)]
+pub struct Point {
+	pub x: u8,
+	pub y: u8,
+}
+
+#[derive(Debug, UDebug)]
+#[debug(rename_all = "snake_case", non_exhaustive = "auto")]
+pub enum Command {
+	Stop,
+	Move {
+		#[fmt(ignore)]
+		id: u32,
+		distance: i16,
+	},
+	#[debug(rename = "raw")]
+	Raw(#[debug("{0:#06x}")] u16),
+	#[debug("flash {0} bytes")]
+	Flash(u32),
+}
+
+#[derive(Display, UDisplay)]
+pub enum Level {
+	#[display("warning: {0}")]
+	Warning(u8),
+	Off,
+}
+
+#[derive(Debug, UDebug)]
+#[debug(placeholder(short))]
+pub struct Holder(pub inner::Opaque, #[debug(placeholder(type_name))] pub inner::Opaque);
+
+#[derive(Debug, UDebug)]
+#[fmt(transparent)]
+pub struct Meters(pub u32);
+
+#[derive(Debug, Display, UDebug, UDisplay)]
+#[fmt(layout = "fields")]
+pub struct Reading {
+	pub sensor: u8,
+	#[fmt(skip_if = Option::is_none)]
+	pub value: Option<u8>,
+	#[display(redact)]
+	pub secret: u8,
+}
+
+#[derive(UDebug)]
+#[udebug("<{}>", self.0)]
+#[debug("{:?}", self.0)]
+pub struct Precedence(pub u8);
+
+fn udebug(value: &impl ufmt::uDebug) -> String {
+	let mut output = String::new();
+	ufmt::uwrite!(output, "{:?}", value).unwrap();
+	output
+}
+
+fn udisplay(value: &impl ufmt::uDisplay) -> String {
+	let mut output = String::new();
+	ufmt::uwrite!(output, "{}", value).unwrap();
+	output
+}
+
+#[test]
+fn struct_test() {
+	let point = Point { x: 7, y: 9 };
+	assert_eq!(udebug(&point), "Point { x: 7, y: 9 }");
+	assert_eq!(udebug(&point), format!("{:?}", point));
+}
+
+#[test]
+fn enum_test() {
+	for command in [
+		Command::Stop,
+		Command::Move { id: 1, distance: -3 },
+		Command::Raw(0xBEEF),
+		Command::Flash(4096),
+	] {
+		assert_eq!(udebug(&command), format!("{:?}", command));
+	}
+	assert_eq!(udebug(&Command::Raw(0x2A)), "raw(0x002a)");
+	assert_eq!(
+		udebug(&Command::Move { id: 1, distance: -3 }),
+		"move { distance: -3, .. }"
+	);
+
+	assert_eq!(udisplay(&Level::Warning(3)), "warning: 3");
+	assert_eq!(udisplay(&Level::Off), "Off");
+}
+
+#[test]
+fn placeholder_test() {
+	let holder = Holder(inner::Opaque, inner::Opaque);
+	assert_eq!(
+		udebug(&holder),
+		format!("Holder(<Opaque>, <{}>)", core::any::type_name::<inner::Opaque>())
+	);
+	assert_eq!(udebug(&holder), format!("{:?}", holder));
+}
+
+#[test]
+fn transparent_test() {
+	assert_eq!(udebug(&Meters(5)), "5");
+}
+
+#[test]
+fn skip_if_test() {
+	for reading in [
+		Reading {
+			sensor: 2,
+			value: None,
+			secret: 1,
+		},
+		Reading {
+			sensor: 2,
+			value: Some(4),
+			secret: 1,
+		},
+	] {
+		assert_eq!(udebug(&reading), format!("{:?}", reading));
+		assert_eq!(udisplay(&reading), format!("{}", reading));
+	}
+	assert_eq!(
+		udisplay(&Reading {
+			sensor: 2,
+			value: Some(4),
+			secret: 1,
+		}),
+		"sensor=2 value=<Option<u8>> secret=***"
+	);
+}
+
+#[test]
+fn precedence_test() {
+	assert_eq!(udebug(&Precedence(1)), "<1>");
+}
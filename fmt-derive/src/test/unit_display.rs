@@ -0,0 +1,53 @@
+use pretty_assertions::assert_eq;
+
+use crate::{Debug, Display};
+
+#[derive(Debug, Display)]
+pub enum Color {
+	Red,
+	DarkGreen,
+	#[display(rename = "azure")]
+	Blue,
+}
+
+#[derive(Debug, Display)]
+#[display(rename_all = "kebab-case")]
+pub enum Kebab {
+	HttpServer,
+	DarkGreen,
+}
+
+#[derive(Debug, Display)]
+#[display(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Mixed {
+	NotFound,
+	Other(u32),
+}
+
+#[derive(Debug, Display)]
+#[display(rename = "unit")]
+pub struct Unit;
+
+#[test]
+fn color_test() {
+	assert_eq!(format!("{:?}", Color::DarkGreen), "DarkGreen");
+	assert_eq!(format!("{}", Color::Red), "Red");
+	assert_eq!(format!("{}", Color::DarkGreen), "DarkGreen");
+	assert_eq!(format!("{}", Color::Blue), "azure");
+}
+
+#[test]
+fn rename_all_test() {
+	assert_eq!(format!("{}", Kebab::HttpServer), "http-server");
+	assert_eq!(format!("{}", Kebab::DarkGreen), "dark-green");
+	assert_eq!(format!("{}", Mixed::NotFound), "NOT_FOUND");
+	assert_eq!(format!("{}", Mixed::Other(1)), "OTHER(1)");
+}
+
+#[test]
+fn padding_test() {
+	assert_eq!(format!("[{:>5}]", Color::Red), "[  Red]");
+	assert_eq!(format!("[{:-<6}]", Unit), "[unit--]");
+	assert_eq!(format!("[{:.2}]", Color::Blue), "[az]");
+	assert_eq!(format!("[{:>5?}]", Color::Red), "[Red]");
+}
@@ -0,0 +1,163 @@
+//! Structured access to the fields of a value, as an alternative to parsing its `Debug` output.
+//!
+//! Types that derive [`fmt_derive::Inspect`](crate::Inspect) implement [`Inspect`], which walks the value and reports
+//! its `struct`s, variants and fields to a [`Visitor`]. A visitor only has to implement the methods that describe the
+//! structure and [`Visitor::visit_debug`]. All other methods fall back to `visit_debug` with the output `Debug` would
+//! print, so that a visitor can override only the kinds of values it treats specially.
+
+use core::fmt::{Debug, Display};
+
+/// Receives the structure and values of a type that implements [`Inspect`].
+///
+/// Every `enter_*` call is matched by the corresponding `exit_*` call, and every field is visited as exactly one value
+/// between [`enter_field`](Visitor::enter_field) and [`exit_field`](Visitor::exit_field), which may itself be a
+/// nested `struct` or tuple.
+pub trait Visitor {
+	/// Starts a `struct` or `struct`-like variant with named fields, which has the (possibly renamed) name `name`.
+	fn enter_struct(&mut self, name: &str);
+
+	/// Ends the `struct` started by the last unmatched [`enter_struct`](Visitor::enter_struct).
+	fn exit_struct(&mut self);
+
+	/// Starts a tuple `struct` or tuple variant, which has the (possibly renamed) name `name`.
+	fn enter_tuple(&mut self, name: &str);
+
+	/// Ends the tuple started by the last unmatched [`enter_tuple`](Visitor::enter_tuple).
+	fn exit_tuple(&mut self);
+
+	/// Starts the field `name` of the current `struct` or tuple. The fields of a tuple are named by their position
+	/// among the fields that are visited, i.e., `0`, `1`, ...
+	fn enter_field(&mut self, name: &str);
+
+	/// Ends the field started by the last unmatched [`enter_field`](Visitor::enter_field).
+	fn exit_field(&mut self);
+
+	/// Visits a unit `struct` or unit variant, which has the (possibly renamed) name `name`.
+	fn visit_unit(&mut self, name: &str);
+
+	/// Visits a value that is only available through its `Debug` output, such as a field that is printed with a
+	/// format string, `with` or `redact`, or whose type implements `Debug` but not [`Inspect`].
+	fn visit_debug(&mut self, value: &dyn Debug);
+
+	/// Visits a field whose type implements neither [`Inspect`] nor `Debug`, which is described by its placeholder.
+	fn visit_placeholder(&mut self, placeholder: &dyn Display) {
+		self.visit_debug(&crate::_rt::DebugDisplay(placeholder));
+	}
+
+	/// Visits a string.
+	fn visit_str(&mut self, value: &str) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits a `bool`.
+	fn visit_bool(&mut self, value: bool) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits a `char`.
+	fn visit_char(&mut self, value: char) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits an unsigned integer of at most 64 bits.
+	fn visit_u64(&mut self, value: u64) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits a signed integer of at most 64 bits.
+	fn visit_i64(&mut self, value: i64) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits a `u128`.
+	fn visit_u128(&mut self, value: u128) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits an `i128`.
+	fn visit_i128(&mut self, value: i128) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits an `f32`.
+	fn visit_f32(&mut self, value: f32) {
+		self.visit_debug(&value);
+	}
+
+	/// Visits an `f64`.
+	fn visit_f64(&mut self, value: f64) {
+		self.visit_debug(&value);
+	}
+}
+
+/// Walks a value and reports its structure to a [`Visitor`], as derived by [`fmt_derive::Inspect`](crate::Inspect).
+pub trait Inspect {
+	/// Reports the value to `visitor`.
+	fn inspect(&self, visitor: &mut dyn Visitor);
+}
+
+impl<T: Inspect + ?Sized> Inspect for &T {
+	fn inspect(&self, visitor: &mut dyn Visitor) {
+		(**self).inspect(visitor);
+	}
+}
+
+impl<T: Inspect + ?Sized> Inspect for &mut T {
+	fn inspect(&self, visitor: &mut dyn Visitor) {
+		(**self).inspect(visitor);
+	}
+}
+
+impl<T: Inspect> Inspect for Option<T> {
+	fn inspect(&self, visitor: &mut dyn Visitor) {
+		match self {
+			Some(value) => {
+				visitor.enter_tuple("Some");
+				visitor.enter_field("0");
+				value.inspect(visitor);
+				visitor.exit_field();
+				visitor.exit_tuple();
+			}
+			None => visitor.visit_unit("None"),
+		}
+	}
+}
+
+impl Inspect for str {
+	fn inspect(&self, visitor: &mut dyn Visitor) {
+		visitor.visit_str(self);
+	}
+}
+
+impl Inspect for bool {
+	fn inspect(&self, visitor: &mut dyn Visitor) {
+		visitor.visit_bool(*self);
+	}
+}
+
+impl Inspect for char {
+	fn inspect(&self, visitor: &mut dyn Visitor) {
+		visitor.visit_char(*self);
+	}
+}
+
+/// Implements [`Inspect`] for primitive types by converting them losslessly to the argument of a visitor method.
+macro_rules! inspect_primitive {
+	($($visit:ident($target:ty): $($ty:ty)*;)*) => {$($(
+		impl Inspect for $ty {
+			#[allow(clippy::unnecessary_cast)]
+			fn inspect(&self, visitor: &mut dyn Visitor) {
+				visitor.$visit(*self as $target);
+			}
+		}
+	)*)*};
+}
+
+inspect_primitive! {
+	visit_u64(u64): u8 u16 u32 u64 usize;
+	visit_i64(i64): i8 i16 i32 i64 isize;
+	visit_u128(u128): u128;
+	visit_i128(i128): i128;
+	visit_f32(f32): f32;
+	visit_f64(f64): f64;
+}